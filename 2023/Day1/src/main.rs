@@ -1,91 +1,412 @@
-use std::collections::HashMap;
+use common::input::load_input;
+use common::output;
+use common::params;
+use common::profile;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::fs;
+use std::str::FromStr;
 
-fn load_input() -> String {
-    include_str!("../inputs/part1.txt").to_string()
+const ENGLISH: [(&str, u32); 10] = [
+    ("zero", 0),
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+const ROMAN: [(&str, u32); 9] = [
+    ("I", 1),
+    ("II", 2),
+    ("III", 3),
+    ("IV", 4),
+    ("V", 5),
+    ("VI", 6),
+    ("VII", 7),
+    ("VIII", 8),
+    ("IX", 9),
+];
+
+// Tokens and the digit each stands for. Plain digits are always included.
+#[derive(Debug, Clone)]
+struct Vocabulary {
+    tokens: Vec<(String, u32)>,
 }
 
-fn part1() {
-    let input = load_input();
-    let answer: u32 = input
-        .trim()
-        .split("\n")
-        .map(|l| {
-            let digits: Vec<char> = l
-                .chars()
-                .filter(|c| match c {
-                    x if x.is_ascii_digit() => true,
-                    _ => false,
-                })
-                .collect();
+impl Vocabulary {
+    fn digits() -> Vocabulary {
+        Vocabulary {
+            tokens: (0..10).map(|d| (d.to_string(), d)).collect(),
+        }
+    }
 
-            vec![
-                digits.first().expect("Expected at least 1 digit"),
-                digits.last().expect("Expected at least 1 digit."),
-            ]
-            .iter()
-            .cloned()
-            .collect::<String>()
-            .parse::<u32>()
-            .expect("Failed to parse number")
-        })
-        .sum();
-    println!("Part 1 Solution: {}", answer);
+    fn with(mut self, words: &[(&str, u32)]) -> Vocabulary {
+        self.tokens
+            .extend(words.iter().map(|(w, d)| (w.to_string(), *d)));
+        self
+    }
+
+    // One `token digit` pair per line; blank lines and `#` comments are skipped.
+    fn load(path: &str) -> Result<Vocabulary, String> {
+        let contents =
+            fs::read_to_string(path).map_err(|e| format!("Couldn't read {}: {}", path, e))?;
+        let mut vocabulary = Vocabulary::digits();
+        for line in contents.lines().map(|l| l.trim()) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_whitespace().collect::<Vec<&str>>().as_slice() {
+                [token, digit] => match digit.parse::<u32>() {
+                    Ok(d) if d < 10 => vocabulary.tokens.push((token.to_string(), d)),
+                    _ => return Err(format!("{:?} isn't a single digit", digit)),
+                },
+                _ => return Err(format!("Expected `token digit`, got {:?}", line)),
+            }
+        }
+        Ok(vocabulary)
+    }
 }
-fn part2() {
-    let input = load_input();
-    let answer: u64 = input
-        .trim()
-        .split("\n")
-        .map(|l| {
-            let mut i = 0;
-            let mut first: Option<char> = None;
-            let mut last: Option<char> = None;
-            while i < l.len() {
-                let subs = &l[i..];
-                i += 1;
-                let mut c = subs.chars().next().unwrap();
-                if !c.is_ascii_digit() {
-                    let num_hash = HashMap::from([
-                        ("zero", '0'),
-                        ("one", '1'),
-                        ("two", '2'),
-                        ("three", '3'),
-                        ("four", '4'),
-                        ("five", '5'),
-                        ("six", '6'),
-                        ("seven", '7'),
-                        ("eight", '8'),
-                        ("nine", '9'),
-                    ]);
-
-                    let found = num_hash.keys().find(|s| subs.starts_with(&s.to_string()));
-
-                    match found {
-                        Some(nc) => c = *num_hash.get(*nc).unwrap(),
-                        _ => continue,
+
+// A built-in table by name, or a path to a file of them.
+impl FromStr for Vocabulary {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "digits" => Ok(Vocabulary::digits()),
+            "english" => Ok(Vocabulary::digits().with(&ENGLISH)),
+            "roman" => Ok(Vocabulary::digits().with(&ROMAN)),
+            path => Vocabulary::load(path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+struct Match {
+    start: usize,
+    end: usize,
+    digit: u32,
+}
+
+// Aho-Corasick over bytes. Every token is found in one pass over the line,
+// overlapping ones included, so "eightwo" yields both eight and two.
+#[derive(Debug)]
+struct Automaton {
+    // `next[state][byte]` with failure links already followed, so each input
+    // byte is a single lookup.
+    next: Vec<[usize; 256]>,
+    // Tokens ending in each state, including those reached by failure links.
+    outputs: Vec<Vec<usize>>,
+    tokens: Vec<(usize, u32)>,
+}
+
+impl Automaton {
+    fn new(vocabulary: &Vocabulary) -> Automaton {
+        let mut children: Vec<[Option<usize>; 256]> = vec![[None; 256]];
+        let mut outputs: Vec<Vec<usize>> = vec![vec![]];
+        for (i, (token, _)) in vocabulary.tokens.iter().enumerate() {
+            let mut state = 0;
+            for byte in token.bytes() {
+                state = match children[state][byte as usize] {
+                    Some(child) => child,
+                    None => {
+                        children.push([None; 256]);
+                        outputs.push(vec![]);
+                        children[state][byte as usize] = Some(children.len() - 1);
+                        children.len() - 1
                     }
-                }
+                };
+            }
+            outputs[state].push(i);
+        }
 
-                if first.is_none() {
-                    first = Some(c);
+        // Breadth first, so a state's failure target is complete before it's used.
+        let mut next = vec![[0; 256]; children.len()];
+        let mut fail = vec![0; children.len()];
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        for byte in 0..256 {
+            if let Some(child) = children[0][byte] {
+                next[0][byte] = child;
+                queue.push_back(child);
+            }
+        }
+        while let Some(state) = queue.pop_front() {
+            let inherited = outputs[fail[state]].clone();
+            outputs[state].extend(inherited);
+            for byte in 0..256 {
+                match children[state][byte] {
+                    Some(child) => {
+                        fail[child] = next[fail[state]][byte];
+                        next[state][byte] = child;
+                        queue.push_back(child);
+                    }
+                    None => next[state][byte] = next[fail[state]][byte],
                 }
-
-                last = Some(c);
             }
+        }
 
-            let res: u64 = vec![first, last]
+        Automaton {
+            next,
+            outputs,
+            tokens: vocabulary
+                .tokens
                 .iter()
-                .map(|v| v.unwrap())
-                .collect::<String>()
-                .parse::<u64>()
-                .unwrap();
+                .map(|(token, digit)| (token.len(), *digit))
+                .collect(),
+        }
+    }
+
+    // Matches come out ordered by where they end.
+    fn matches<'a>(&'a self, line: &'a str) -> impl Iterator<Item = Match> + 'a {
+        line.bytes()
+            .enumerate()
+            .scan(0, |state, (i, byte)| {
+                *state = self.next[*state][byte as usize];
+                Some((i + 1, *state))
+            })
+            .flat_map(move |(end, state)| {
+                self.outputs[state].iter().map(move |t| {
+                    let (length, digit) = self.tokens[*t];
+                    Match {
+                        start: end - length,
+                        end,
+                        digit,
+                    }
+                })
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+struct Calibration {
+    first: Match,
+    last: Match,
+}
+
+impl Calibration {
+    // The earliest starting and latest ending matches, preferring the longer
+    // token where two start or end together. Numerals nest, so the latest to
+    // start can sit inside the last one: VIII also holds III, II and I.
+    fn from_matches(matches: &[Match]) -> Option<Calibration> {
+        Some(Calibration {
+            first: *matches.iter().min_by_key(|m| (m.start, Reverse(m.end)))?,
+            last: *matches.iter().max_by_key(|m| (m.end, Reverse(m.start)))?,
+        })
+    }
+
+    fn value(&self) -> u32 {
+        self.first.digit * 10 + self.last.digit
+    }
+}
+
+// What to do with a line that has no digit at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MissingPolicy {
+    Skip,
+    Zero,
+    Fail,
+}
+
+impl FromStr for MissingPolicy {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "skip" => Ok(MissingPolicy::Skip),
+            "zero" => Ok(MissingPolicy::Zero),
+            "fail" => Ok(MissingPolicy::Fail),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct MissingDigits {
+    line: usize,
+    text: String,
+}
+
+impl Display for MissingDigits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {} has no digits: {:?}", self.line, self.text)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct LineReport {
+    line: usize,
+    #[serde(skip)]
+    text: String,
+    matches: Vec<Match>,
+    calibration: Option<Calibration>,
+    // None for a line that was skipped.
+    value: Option<u32>,
+}
 
-            res
+impl LineReport {
+    fn token(&self, m: &Match) -> &str {
+        &self.text[m.start..m.end]
+    }
+}
+
+impl Display for LineReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Line {}: {:?}", self.line, self.text)?;
+        let recognised = self
+            .matches
+            .iter()
+            .map(|m| format!("{} @{}..{} -> {}", self.token(m), m.start, m.end, m.digit))
+            .collect::<Vec<String>>();
+        if !recognised.is_empty() {
+            writeln!(f, "  {}", recognised.join(", "))?;
+        }
+        match (self.calibration, self.value) {
+            (Some(c), _) => write!(
+                f,
+                "  first {} @{}, last {} @{} => {}",
+                self.token(&c.first),
+                c.first.start,
+                self.token(&c.last),
+                c.last.start,
+                c.value()
+            ),
+            (None, Some(value)) => write!(f, "  no digits, counted as {}", value),
+            (None, None) => write!(f, "  no digits, skipped"),
+        }
+    }
+}
+
+fn calibrate_all(
+    input: &str,
+    vocabulary: &Vocabulary,
+    policy: MissingPolicy,
+) -> Result<Vec<LineReport>, MissingDigits> {
+    let automaton = Automaton::new(vocabulary);
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, text)| {
+            let matches: Vec<Match> = automaton.matches(text).collect();
+            let calibration = Calibration::from_matches(&matches);
+            let value = match (calibration, policy) {
+                (Some(c), _) => Some(c.value()),
+                (None, MissingPolicy::Skip) => None,
+                (None, MissingPolicy::Zero) => Some(0),
+                (None, MissingPolicy::Fail) => {
+                    return Err(MissingDigits {
+                        line: i + 1,
+                        text: text.to_string(),
+                    })
+                }
+            };
+            Ok(LineReport {
+                line: i + 1,
+                text: text.to_string(),
+                matches,
+                calibration,
+                value,
+            })
         })
-        .sum();
-    println!("Part 2 Solution: {}", answer);
+        .collect()
 }
+
+// A missing digit fails only the part it turns up in, so the other part
+// still gets its answer.
+fn solve(part: u32, vocabulary: &Vocabulary, detail: &str) -> Result<u64, MissingDigits> {
+    let input = profile::parse(|| load_input("part1.txt"));
+    let policy = params::get("missing", MissingPolicy::Fail);
+    let reports = calibrate_all(&input, vocabulary, policy)?;
+
+    // Skipped and zeroed lines change the answer, so they're always named.
+    for report in reports.iter().filter(|r| r.calibration.is_none()) {
+        let counted = match report.value {
+            Some(value) => format!("counted as {}", value),
+            None => "skipped".to_string(),
+        };
+        eprintln!(
+            "Part {}: line {} has no digits, {}: {:?}",
+            part, report.line, counted, report.text
+        );
+    }
+
+    // `explain=true` prints how every line's value was found ahead of the answer.
+    if params::get("explain", false) {
+        println!("Part {}", part);
+        reports.iter().for_each(|r| println!("{}", r));
+    }
+    output::detail(detail, || reports.clone());
+    Ok(reports.iter().filter_map(|r| r.value).map(u64::from).sum())
+}
+
+fn part1() {
+    match solve(1, &Vocabulary::digits(), "digit_matches") {
+        Ok(answer) => output::answer(1, answer),
+        Err(e) => eprintln!("Part 1 failed: {}", e),
+    }
+}
+
+fn part2() {
+    let vocabulary = params::get("vocabulary", Vocabulary::digits().with(&ENGLISH));
+    match solve(2, &vocabulary, "token_matches") {
+        Ok(answer) => output::answer(2, answer),
+        Err(e) => eprintln!("Part 2 failed: {}", e),
+    }
+}
+
 fn main() {
-    part1();
-    part2();
+    profile::part(1, part1);
+    profile::part(2, part2);
+    output::finish();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(vocabulary: &str, line: &str) -> Option<u32> {
+        let automaton = Automaton::new(&vocabulary.parse().unwrap());
+        let matches: Vec<Match> = automaton.matches(line).collect();
+        Calibration::from_matches(&matches).map(|c| c.value())
+    }
+
+    #[test]
+    fn roman_numerals_use_the_whole_last_numeral() {
+        assert_eq!(value("roman", "xVIIIx"), Some(88));
+        assert_eq!(value("roman", "IV"), Some(44));
+        assert_eq!(value("roman", "IX"), Some(99));
+        assert_eq!(value("roman", "IIxVI"), Some(26));
+    }
+
+    #[test]
+    fn overlapping_words_count_at_both_ends() {
+        assert_eq!(value("english", "eightwo"), Some(82));
+        assert_eq!(value("english", "7pqrstsixteen"), Some(76));
+        assert_eq!(value("digits", "a1b2c3d4e5f"), Some(15));
+        assert_eq!(value("digits", "treb"), None);
+    }
+
+    #[test]
+    fn missing_digits_follow_the_policy() {
+        let digits = Vocabulary::digits();
+        let input = "a1b\nseven\n2";
+        let values = |policy| {
+            calibrate_all(input, &digits, policy)
+                .map(|reports| reports.iter().map(|r| r.value).collect::<Vec<_>>())
+        };
+
+        assert_eq!(
+            values(MissingPolicy::Skip).unwrap(),
+            [Some(11), None, Some(22)]
+        );
+        assert_eq!(
+            values(MissingPolicy::Zero).unwrap(),
+            [Some(11), Some(0), Some(22)]
+        );
+        assert_eq!(values(MissingPolicy::Fail).unwrap_err().line, 2);
+    }
 }
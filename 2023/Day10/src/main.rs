@@ -1,3 +1,8 @@
+use common::explore::{self, Explorable, Overlay};
+use common::input::load_input;
+use common::output;
+use common::profile;
+use serde::Serialize;
 use std::convert::TryInto;
 use std::ops::Add;
 use std::result::Result;
@@ -37,7 +42,7 @@ impl Direction {
         }
     }
 }
-#[derive(Debug, Clone, Copy, Eq, PartialOrd)]
+#[derive(Debug, Clone, Copy, Eq, PartialOrd, Serialize)]
 struct Coordinates {
     x: i32,
     y: i32,
@@ -462,18 +467,37 @@ impl Board {
     }
 }
 
+impl Explorable for Board {
+    fn size(&self) -> (usize, usize) {
+        let bounds = self.bounds();
+        (bounds.y as usize, bounds.x as usize)
+    }
+
+    fn glyph(&self, row: usize, column: usize) -> char {
+        match self.tiles[row][column].entity {
+            Entity::Start => '★',
+            Entity::Pipe(p) => p.char_representation(),
+            Entity::Blank => '.',
+        }
+    }
+
+    fn describe(&self, row: usize, column: usize) -> String {
+        format!("{:?}", self.tiles[row][column].entity)
+    }
+}
+
 fn load_board() -> Board {
-    include_str!("../inputs/part1.txt")
+    load_input("part1.txt")
         .parse::<Board>()
         .expect("Expected to parse a board")
 }
 
 fn part1() {
-    let mut board = load_board();
+    let mut board = profile::parse(load_board);
     let len = board.loop_len();
     let answer = len / 2;
     println!("Len: {}", len);
-    println!("Part 1 Answer: {}", answer);
+    output::answer(1, answer);
 }
 
 fn print_rep(board: &Board, mloop: &Vec<Coordinates>, enclosed: &Vec<Coordinates>) {
@@ -481,12 +505,9 @@ fn print_rep(board: &Board, mloop: &Vec<Coordinates>, enclosed: &Vec<Coordinates
     println!("{}", rep);
 }
 
-fn part2() {
-    let mut board = load_board();
-    let mut mloop = board.main_loop();
-    mloop.sort();
-
-    let enclosed = board
+// Expects the main loop sorted left to right.
+fn enclosed_tiles(board: &Board, mloop: &Vec<Coordinates>) -> Vec<Coordinates> {
+    board
         .tiles
         .iter()
         .flatten()
@@ -529,15 +550,66 @@ fn part2() {
                 % 2
                 != 0
         })
-        .collect::<Vec<Coordinates>>();
+        .collect::<Vec<Coordinates>>()
+}
+
+fn part2() {
+    let mut board = profile::parse(load_board);
+    let mut mloop = board.main_loop();
+    mloop.sort();
+
+    let enclosed = enclosed_tiles(&board, &mloop);
 
     print_rep(&board, &mloop, &enclosed);
     // println!("Board: {}", board);
     let nenclosed = enclosed.len();
 
-    println!("Num Enclosed: {}", nenclosed);
+    output::detail("loop", || mloop.clone());
+    output::detail("enclosed", || enclosed.clone());
+
+    output::answer(2, nenclosed);
+}
+fn explore_board() {
+    let mut board = load_board();
+    let mloop = board.main_loop();
+    let mut sorted_loop = mloop.clone();
+    sorted_loop.sort();
+
+    let mut loop_overlay = Overlay::new("Main loop");
+    mloop.iter().enumerate().for_each(|(i, c)| {
+        let (row, column) = (c.y as usize, c.x as usize);
+        let distance = (i + 1).min(mloop.len() - i - 1);
+        loop_overlay.mark(
+            row,
+            column,
+            board.glyph(row, column),
+            format!("Loop step {}, {} from start", i + 1, distance),
+        );
+    });
+
+    let mut enclosed_overlay = Overlay::new("Enclosed");
+    enclosed_overlay.step(
+        enclosed_tiles(&board, &sorted_loop)
+            .iter()
+            .map(|c| explore::Mark {
+                row: c.y as usize,
+                column: c.x as usize,
+                glyph: '■',
+                note: "Enclosed".to_string(),
+            })
+            .collect(),
+    );
+
+    explore::explore(&board, &[loop_overlay, enclosed_overlay]);
 }
+
 fn main() {
-    part1();
-    part2();
+    if explore::requested() {
+        explore_board();
+        return;
+    }
+
+    profile::part(1, part1);
+    profile::part(2, part2);
+    output::finish();
 }
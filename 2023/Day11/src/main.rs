@@ -1,3 +1,8 @@
+use common::input::load_input;
+use common::output;
+use common::params;
+use common::profile;
+
 type Galaxy = (usize, usize);
 
 struct SpaceTracker {
@@ -45,8 +50,8 @@ fn distance_between(galaxy1: Galaxy, galaxy2: Galaxy) -> usize {
     galaxy1.0.abs_diff(galaxy2.0) + galaxy1.1.abs_diff(galaxy2.1)
 }
 
-fn main() {
-    let input = include_str!("../inputs/part1.txt");
+fn load_galaxies() -> (SpaceTracker, Vec<Galaxy>) {
+    let input = load_input("part1.txt");
 
     let nrows = input.lines().count();
     let ncols = input.lines().nth(0).unwrap().len();
@@ -70,38 +75,41 @@ fn main() {
 
     st.process_seen(&galaxies);
 
-    //////// Part 1 Solution /////////////
-    let adjusted_galaxies = st.adjust_galaxies(&galaxies, 2);
+    (st, galaxies)
+}
 
-    let result: usize = adjusted_galaxies
+fn total_distance(galaxies: &Vec<Galaxy>) -> usize {
+    galaxies
         .iter()
         .enumerate()
         .map(|(i, g1)| {
-            adjusted_galaxies
+            galaxies
                 .iter()
                 .skip(i + 1)
                 .map(|g2| distance_between(*g1, *g2))
                 .sum::<usize>()
         })
-        .sum();
+        .sum()
+}
 
-    println!("Part 1 Result: {}", result);
-    ////////End Part 1 Solution /////////////
-    //
-    //////// Part 2 Solution /////////////
-    let adjusted_galaxies_pt2 = st.adjust_galaxies(&galaxies, 1000000);
+fn part1() {
+    let (mut st, galaxies) = profile::parse(load_galaxies);
+    let adjusted_galaxies = st.adjust_galaxies(&galaxies, 2);
+    let result = total_distance(&adjusted_galaxies);
 
-    let result_pt2: usize = adjusted_galaxies_pt2
-        .iter()
-        .enumerate()
-        .map(|(i, g1)| {
-            adjusted_galaxies_pt2
-                .iter()
-                .skip(i + 1)
-                .map(|g2| distance_between(*g1, *g2))
-                .sum::<usize>()
-        })
-        .sum();
+    output::answer(1, result);
+}
 
-    println!("Part 2 Result: {}", result_pt2);
+fn part2() {
+    let (mut st, galaxies) = profile::parse(load_galaxies);
+    let adjusted_galaxies = st.adjust_galaxies(&galaxies, params::get("expansion", 1000000));
+    let result = total_distance(&adjusted_galaxies);
+
+    output::answer(2, result);
+}
+
+fn main() {
+    profile::part(1, part1);
+    profile::part(2, part2);
+    output::finish();
 }
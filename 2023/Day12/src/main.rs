@@ -1,3 +1,7 @@
+use common::input::load_input;
+use common::output;
+use common::parallel;
+use common::profile;
 use itertools::Itertools;
 use log::{debug, info, trace, LevelFilter};
 use std::cell::RefCell;
@@ -103,7 +107,7 @@ struct InputData {
     records: Vec<RecordType>,
     groups: Vec<usize>,
     position_cache: RefCell<HashMap<EvaluationPosition, usize>>,
-    arrangement_cache: RefCell<HashMap<EvaluationPosition, Vec<EvaluationPosition>>>,
+    arrangement_cache: RefCell<HashMap<EvaluationPosition, Vec<Vec<EvaluationPosition>>>>,
 }
 impl InputData {
     fn new(records: Vec<RecordType>, groups: Vec<usize>) -> InputData {
@@ -205,17 +209,17 @@ impl InputData {
     fn possible_arrangements(
         &self,
         start_position: EvaluationPosition,
-    ) -> &Vec<Vec<EvaluationPosition>> {
+    ) -> Vec<Vec<EvaluationPosition>> {
         if let Some(cache_hit) = self.arrangement_cache.borrow().get(&start_position) {
             trace!("Cache Hit!: {:?}", start_position);
-            return cache_hit;
+            return cache_hit.clone();
         }
 
         let possible_positions = self.possible_positions(start_position);
         let possible_count = possible_positions.clone().count();
-        let value: Vec<&&Vec<EvaluationPosition>> =
+        let value: Vec<Vec<EvaluationPosition>> =
             if start_position.group_index == self.groups.len() - 1 {
-                possible_positions.map(|p| &&vec![p]).collect()
+                possible_positions.map(|p| vec![p]).collect()
             } else {
                 possible_positions
                     .map(|pos| {
@@ -235,7 +239,7 @@ impl InputData {
                         arrangements
                     })
                     .concat()
-                    .iter()
+                    .into_iter()
                     .dedup()
                     .collect()
             };
@@ -252,8 +256,8 @@ impl InputData {
         }
         self.arrangement_cache
             .borrow_mut()
-            .insert(start_position, &value);
-        &value
+            .insert(start_position, value.clone());
+        value
     }
 
     fn num_arrangements(&self, start_position: EvaluationPosition) -> usize {
@@ -369,110 +373,120 @@ fn num_variants(
 }
 
 fn part1() {
-    let input = include_str!("../inputs/part1.txt");
-
-    let result: usize = input
-        .lines()
-        .map(|l| {
-            let parts: Vec<&str> = l.split(" ").collect();
-            (
-                parts
-                    .first()
-                    .unwrap()
-                    .trim()
-                    .chars()
-                    .map(|c| RecordType::from(c))
-                    .collect::<Vec<RecordType>>(),
-                parts[1]
-                    .trim()
-                    .split(",")
-                    .map(|s| s.parse::<usize>().unwrap())
-                    .collect::<Vec<usize>>(),
-            )
-        })
-        .map(|(r, g)| {
-            let nvariants = num_variants(r.clone(), g.clone(), 1);
+    let input = load_input("part1.txt");
 
-            debug!(
-                "{}, {:?} ==> {}",
-                r.iter().map(|r| r.to_string()).collect::<String>(),
-                g,
-                nvariants
-            );
+    let records = profile::parse(|| {
+        input
+            .lines()
+            .map(|l| {
+                let parts: Vec<&str> = l.split(" ").collect();
+                (
+                    parts
+                        .first()
+                        .unwrap()
+                        .trim()
+                        .chars()
+                        .map(|c| RecordType::from(c))
+                        .collect::<Vec<RecordType>>(),
+                    parts[1]
+                        .trim()
+                        .split(",")
+                        .map(|s| s.parse::<usize>().unwrap())
+                        .collect::<Vec<usize>>(),
+                )
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let result: usize = parallel::map(&records, |(r, g)| {
+        let nvariants = num_variants(r.clone(), g.clone(), 1);
 
+        debug!(
+            "{}, {:?} ==> {}",
+            r.iter().map(|r| r.to_string()).collect::<String>(),
+            g,
             nvariants
-        })
-        .sum();
+        );
 
-    info!("Part 1 Result {:?}", result);
+        nvariants
+    })
+    .into_iter()
+    .sum();
+
+    output::answer(1, result);
 }
 fn part2() {
-    let input = include_str!("../inputs/part2.txt");
-
-    let result: usize = input
-        .lines()
-        .map(|l| {
-            let parts: Vec<&str> = l.split(" ").collect();
-            InputData::new(
-                parts
-                    .first()
-                    .unwrap()
-                    .trim()
-                    .chars()
-                    .map(|c| RecordType::from(c))
-                    .collect::<Vec<RecordType>>(),
-                parts[1]
-                    .trim()
-                    .split(",")
-                    .map(|s| s.parse::<usize>().unwrap())
-                    .collect::<Vec<usize>>(),
-            )
-        })
-        .map(|rec| {
-            let binding = rec.possible_arrangements(EvaluationPosition::start());
-            rec.position_cache.borrow_mut().clear();
-            let variants: Vec<&&Vec<EvaluationPosition>> = binding
-                .into_iter()
-                .filter(|variant_positions| {
-                    rec.known_broken_groups()
-                        .into_iter()
-                        .filter(|known_broken_group| {
-                            !variant_positions
-                                .into_iter()
-                                .find(|pos| {
-                                    pos.fully_contains(
-                                        rec.groups[pos.group_index],
-                                        known_broken_group.clone(),
-                                    )
-                                })
-                                .is_some()
-                        })
-                        .count()
-                        == 0
-                })
-                .collect();
-            // .count()
-            // .collect();
-            // variants.clone().iter().for_each(|v| {
-            //     debug!(
-            //         "Variant: {:?}",
-            //         v.iter().map(|pos| pos.record_index).collect::<Vec<_>>()
-            //     )
-            // });
-            // let count = variants.count();
-            info!(
-                "{}, {:?} ==> {}",
-                EvaluationPosition::start().get_records_string(&rec),
-                rec.groups,
-                variants.len()
-            );
+    let input = load_input("part2.txt");
+
+    let records = profile::parse(|| {
+        input
+            .lines()
+            .map(|l| {
+                let parts: Vec<&str> = l.split(" ").collect();
+                InputData::new(
+                    parts
+                        .first()
+                        .unwrap()
+                        .trim()
+                        .chars()
+                        .map(|c| RecordType::from(c))
+                        .collect::<Vec<RecordType>>(),
+                    parts[1]
+                        .trim()
+                        .split(",")
+                        .map(|s| s.parse::<usize>().unwrap())
+                        .collect::<Vec<usize>>(),
+                )
+            })
+            .collect::<Vec<_>>()
+    });
 
-            // variants.count()
+    let result: usize = parallel::map_owned(records, |rec| {
+        let binding = rec.possible_arrangements(EvaluationPosition::start());
+        rec.position_cache.borrow_mut().clear();
+        let variants: Vec<&Vec<EvaluationPosition>> = binding
+            .iter()
+            .filter(|variant_positions| {
+                rec.known_broken_groups()
+                    .into_iter()
+                    .filter(|known_broken_group| {
+                        !variant_positions
+                            .into_iter()
+                            .find(|pos| {
+                                pos.fully_contains(
+                                    rec.groups[pos.group_index],
+                                    known_broken_group.clone(),
+                                )
+                            })
+                            .is_some()
+                    })
+                    .count()
+                    == 0
+            })
+            .collect();
+        // .count()
+        // .collect();
+        // variants.clone().iter().for_each(|v| {
+        //     debug!(
+        //         "Variant: {:?}",
+        //         v.iter().map(|pos| pos.record_index).collect::<Vec<_>>()
+        //     )
+        // });
+        // let count = variants.count();
+        info!(
+            "{}, {:?} ==> {}",
+            EvaluationPosition::start().get_records_string(&rec),
+            rec.groups,
             variants.len()
-        })
-        .sum();
+        );
+
+        // variants.count()
+        variants.len()
+    })
+    .into_iter()
+    .sum();
 
-    info!("Part 2 Result {:?}", result);
+    output::answer(2, result);
 }
 fn main() {
     let env = Env::default().filter_or("MY_LOG_LEVEL", "info");
@@ -480,5 +494,6 @@ fn main() {
     env_logger::init_from_env(env);
     log::set_max_level(LevelFilter::Debug);
     // part1();
-    part2();
+    profile::part(2, part2);
+    output::finish();
 }
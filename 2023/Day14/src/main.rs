@@ -1,11 +1,9 @@
+use common::bitgrid::{BitGrid, BitPlanes};
+use common::input::load_input;
+use common::output;
+use common::profile;
 use itertools::Itertools;
-use std::{
-    cell::RefCell,
-    collections::HashMap,
-    fs::File,
-    io::{BufReader, Read},
-    str::FromStr,
-};
+use std::{cell::RefCell, collections::HashMap, str::FromStr};
 
 #[derive(Debug, Clone, Copy, Hash, Ord, PartialEq, PartialOrd, Eq)]
 enum Rock {
@@ -24,6 +22,27 @@ impl From<char> for Rock {
     }
 }
 
+// Round rocks live in the first plane and square rocks in the second.
+impl From<Rock> for u8 {
+    fn from(value: Rock) -> Self {
+        match value {
+            Rock::None => 0,
+            Rock::Round => 1,
+            Rock::Square => 2,
+        }
+    }
+}
+
+impl From<u8> for Rock {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => Rock::Round,
+            2 => Rock::Square,
+            _ => Rock::None,
+        }
+    }
+}
+
 impl ToString for Rock {
     fn to_string(&self) -> String {
         match self {
@@ -44,12 +63,12 @@ enum CardinalDirection {
 
 #[derive(Debug, Clone)]
 struct Platform {
-    items: Vec<Vec<RefCell<Rock>>>,
+    items: BitPlanes,
     cycle_tracker: Option<CycleTracker>,
 }
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 struct PlatformState {
-    item_runs: Vec<(usize, Rock)>,
+    round: BitGrid,
 }
 #[derive(Debug, Clone)]
 struct CycleTracker {
@@ -68,59 +87,64 @@ impl CycleTracker {
     }
 }
 
-impl Platform {
-    fn num_columns(&self) -> usize {
-        self.items[0].len()
+// Rolls every round rock towards row 0, or the last row when `reverse` is
+// set, 64 columns at a time. Rows behind the current one have already settled,
+// so a rock keeps moving until the row ahead of it is occupied.
+fn roll(round: &mut BitGrid, square: &BitGrid, reverse: bool) {
+    let rows: Vec<usize> = match reverse {
+        false => (0..round.rows()).collect(),
+        true => (0..round.rows()).rev().collect(),
+    };
+
+    for index in 0..round.words_per_row() {
+        for (i, &row) in rows.iter().enumerate() {
+            let mut moving = round.word(row, index);
+            for window in rows[..=i].windows(2).rev() {
+                let (ahead, current) = (window[0], window[1]);
+                let free = !(round.word(ahead, index) | square.word(ahead, index));
+                moving &= free;
+                if moving == 0 {
+                    break;
+                }
+                round.set_word(current, index, round.word(current, index) & !moving);
+                round.set_word(ahead, index, round.word(ahead, index) | moving);
+            }
+        }
     }
+}
+
+impl Platform {
     fn num_rows(&self) -> usize {
-        self.items.len()
+        self.items.rows()
     }
 
-    fn sort_direction(&self, cells: Vec<&RefCell<Rock>>, direction: CardinalDirection) {
-        cells
-            .iter()
-            .map(|c| *c.borrow())
-            .group_by(|r| *r != Rock::Square)
-            .into_iter()
-            .map(|(_, vals)| {
-                vals.sorted_by(|a, b| match direction {
-                    CardinalDirection::North | CardinalDirection::West => a.cmp(b),
-                    CardinalDirection::South | CardinalDirection::East => b.cmp(a),
-                })
-            })
-            .flatten()
-            .zip(cells.iter())
-            .for_each(|(r, rc)| *rc.borrow_mut() = r)
-    }
+    // East and west roll the transposed platform, so each tilt still works on
+    // whole row words.
+    fn tilt(&mut self, direction: CardinalDirection) {
+        let sideways = matches!(direction, CardinalDirection::East | CardinalDirection::West);
+        let reverse = matches!(
+            direction,
+            CardinalDirection::South | CardinalDirection::East
+        );
 
-    fn tilt(&self, direction: CardinalDirection) {
-        match direction {
-            CardinalDirection::North | CardinalDirection::South => {
-                (0..self.num_columns()).for_each(|i| {
-                    self.sort_direction(
-                        self.items.iter().map(|r| &r[i]).into_iter().collect(),
-                        direction.clone(),
-                    )
-                });
-            }
-            CardinalDirection::East | CardinalDirection::West => {
-                (0..self.num_rows()).for_each(|i| {
-                    self.sort_direction(self.items[i].iter().collect(), direction.clone())
-                });
-            }
+        if sideways {
+            self.items = self.items.transpose();
+        }
+        if let [round, square] = self.items.planes_mut() {
+            roll(round, square, reverse);
+        }
+        if sideways {
+            self.items = self.items.transpose();
         }
     }
     #[allow(dead_code)]
     fn print(&self) {
-        println!(
-            "{}",
-            (0..self.items.first().unwrap().len()).map(|_| "-").join("")
-        );
-        self.items.iter().for_each(|r| {
+        println!("{}", (0..self.items.columns()).map(|_| "-").join(""));
+        (0..self.num_rows()).for_each(|r| {
             println!(
                 "{}",
-                r.iter()
-                    .map(|i| (*i.borrow()).to_string())
+                (0..self.items.columns())
+                    .map(|c| Rock::from(self.items.get(r, c)).to_string())
                     .collect::<String>()
             );
         });
@@ -129,27 +153,20 @@ impl Platform {
 
     fn total_load(&self) -> usize {
         let difference = self.num_rows();
-        self.items.iter().enumerate().fold(0, |acc, (i, row)| {
+        let round = self.items.plane(0);
+        (0..difference).fold(0, |acc, i| {
             let multiplier = difference - i;
-            acc + multiplier * row.iter().filter(|rc| *rc.borrow() == Rock::Round).count()
+            acc + multiplier * round.row_count(i)
         })
     }
 
     fn state(&self) -> PlatformState {
         PlatformState {
-            item_runs: self
-                .items
-                .clone()
-                .into_iter()
-                .flatten()
-                .group_by(|rc| *rc.borrow())
-                .into_iter()
-                .map(|(group, elems)| (elems.count(), group))
-                .collect::<Vec<(usize, Rock)>>(),
+            round: self.items.plane(0).clone(),
         }
     }
 
-    fn cycle(&self) {
+    fn cycle(&mut self) {
         [
             CardinalDirection::North,
             CardinalDirection::West,
@@ -213,48 +230,32 @@ impl FromStr for Platform {
     type Err = ();
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Platform {
-            items: s
-                .trim()
-                .split("\n")
-                .map(|l| {
-                    l.chars()
-                        .map(|c| RefCell::new(Rock::from(c)))
-                        .collect::<Vec<RefCell<Rock>>>()
-                })
-                .collect(),
+            items: BitPlanes::parse(s.trim(), 2, |c| Rock::from(c).into()),
             cycle_tracker: None,
         })
     }
 }
 
-fn load_input(input_name: &str) -> String {
-    let input_file = File::open(format!("inputs/{}", input_name)).unwrap();
-
-    let mut data: String = String::new();
-    let _ = BufReader::new(input_file).read_to_string(&mut data);
-
-    data
-}
-
 fn part1() {
-    let input = load_input("part1.txt");
-    let platform = input.parse::<Platform>().unwrap();
+    let input = profile::parse(|| load_input("part1.txt"));
+    let mut platform = profile::parse(|| input.parse::<Platform>()).unwrap();
     platform.tilt(CardinalDirection::North);
     let result = platform.total_load();
 
-    println!("Part 1 Result: {}", result);
+    output::answer(1, result);
 }
 
 fn part2() {
-    let input = load_input("part1.txt");
-    let mut platform = input.parse::<Platform>().unwrap();
+    let input = profile::parse(|| load_input("part1.txt"));
+    let mut platform = profile::parse(|| input.parse::<Platform>()).unwrap();
     platform.cycle_n(1000000000);
     let result = platform.total_load();
 
-    println!("Part 2 Result: {}", result);
+    output::answer(2, result);
 }
 
 fn main() {
-    part1();
-    part2();
+    profile::part(1, part1);
+    profile::part(2, part2);
+    output::finish();
 }
@@ -1,19 +1,11 @@
+use common::input::load_input;
+use common::output;
+use common::profile;
 use std::{
-    fs::File,
-    io::{BufReader, Read},
     ops::{Deref, DerefMut},
     str::FromStr,
 };
 
-fn load_input(input_name: &str) -> String {
-    let input_file = File::open(format!("inputs/{}", input_name)).unwrap();
-
-    let mut data: String = String::new();
-    let _ = BufReader::new(input_file).read_to_string(&mut data);
-
-    data
-}
-
 #[derive(Debug, Clone, Hash)]
 enum Operation {
     Store(String, u8),
@@ -203,26 +195,25 @@ impl StorageState {
 
 #[allow(dead_code)]
 fn part1() {
-    let input = load_input("part1.txt");
-    let result = input
-        .parse::<StorageState>()
+    let input = profile::parse(|| load_input("part1.txt"));
+    let result = profile::parse(|| input.parse::<StorageState>())
         .expect("Should have been able to parse input")
         .hash();
 
-    println!("Part 1 Result: {}", result);
+    output::answer(1, result);
 }
 
 #[allow(dead_code)]
 fn part2() {
-    let input = load_input("part1.txt");
-    let result = input
-        .parse::<StorageState>()
+    let input = profile::parse(|| load_input("part1.txt"));
+    let result = profile::parse(|| input.parse::<StorageState>())
         .expect("Should have been able to parse input")
         .run();
-    println!("Part 2 Result: {}", result);
+    output::answer(2, result);
 }
 
 fn main() {
-    part1();
-    part2();
+    profile::part(1, part1);
+    profile::part(2, part2);
+    output::finish();
 }
@@ -1,21 +1,16 @@
+use common::bitgrid::BitPlanes;
+use common::explore::{self, Explorable, Overlay};
+use common::input::load_input;
+use common::output;
+use common::parallel;
+use common::profile;
 use std::{
-    cell::{RefCell, RefMut},
-    collections::{HashSet, VecDeque},
-    fs::File,
-    io::{BufReader, Read, Write},
+    collections::VecDeque,
+    mem,
     ops::{Add, Deref, DerefMut, Sub},
     str::FromStr,
 };
 
-fn load_input(input_name: &str) -> String {
-    let input_file = File::open(format!("inputs/{}", input_name)).unwrap();
-
-    let mut data: String = String::new();
-    let _ = BufReader::new(input_file).read_to_string(&mut data);
-
-    data
-}
-
 #[derive(Debug)]
 enum Optic {
     ForwardMirror,
@@ -38,6 +33,16 @@ impl From<char> for Optic {
 }
 
 impl Optic {
+    fn char_representation(&self) -> char {
+        match self {
+            Self::ForwardMirror => '/',
+            Self::BackwardMirror => '\\',
+            Self::VerticalSplitter => '-',
+            Self::HorizontalSplitter => '|',
+            Self::Empty => '.',
+        }
+    }
+
     fn apply_optic(&self, coord_delta: Coordinate) -> Vec<Coordinate> {
         match self {
             Self::Empty => vec![coord_delta],
@@ -84,6 +89,26 @@ impl Coordinate {
         (self.row, self.column)
     }
 
+    // Which of the four unit steps a delta is, to index the visited planes.
+    fn heading(self) -> usize {
+        match self.as_tuple() {
+            (-1, 0) => 0,
+            (1, 0) => 1,
+            (0, -1) => 2,
+            (0, 1) => 3,
+            _ => panic!("Expected a unit step."),
+        }
+    }
+
+    fn from_heading(heading: usize) -> Coordinate {
+        [
+            Coordinate::new(-1, 0),
+            Coordinate::new(1, 0),
+            Coordinate::new(0, -1),
+            Coordinate::new(0, 1),
+        ][heading]
+    }
+
     fn as_queued(&self, from: Coordinate) -> QueuedVisit {
         QueuedVisit {
             to_coordinate: self.clone(),
@@ -115,61 +140,35 @@ impl Add for Coordinate {
 struct Node {
     optic: Optic,
     index: Coordinate,
-    visited_from: HashSet<Coordinate>,
 }
 
 impl Node {
     fn new(optic_char: char, index: Coordinate) -> Self {
         let optic = Optic::from(optic_char);
-        Node {
-            optic,
-            index,
-            visited_from: HashSet::new(),
-        }
-    }
-
-    fn visit(&mut self, from_index: Coordinate) -> Vec<QueuedVisit> {
-        match self.visited_from.contains(&from_index) {
-            true => vec![],
-            false => {
-                self.visited_from.insert(from_index);
-                let delta = self.index - from_index;
-                self.optic
-                    .apply_optic(delta)
-                    .into_iter()
-                    .map(|cd| self.index + cd)
-                    .map(|cd| cd.as_queued(self.index))
-                    .collect::<Vec<QueuedVisit>>()
-            }
-        }
-    }
-
-    fn energized(&self) -> bool {
-        self.visited_from.len() > 0
-    }
-
-    fn energized_char(&self) -> char {
-        match self.energized() {
-            true => '#',
-            false => '.',
-        }
+        Node { optic, index }
     }
 
-    fn reset(&mut self) {
-        self.visited_from.clear();
+    fn visit(&self, from_index: Coordinate) -> Vec<QueuedVisit> {
+        let delta = self.index - from_index;
+        self.optic
+            .apply_optic(delta)
+            .into_iter()
+            .map(|cd| self.index + cd)
+            .map(|cd| cd.as_queued(self.index))
+            .collect::<Vec<QueuedVisit>>()
     }
 }
 #[derive(Debug)]
-struct NodeList(Vec<RefCell<Node>>);
+struct NodeList(Vec<Node>);
 impl Deref for NodeList {
-    type Target = Vec<RefCell<Node>>;
-    fn deref(&self) -> &Vec<RefCell<Node>> {
+    type Target = Vec<Node>;
+    fn deref(&self) -> &Vec<Node> {
         &self.0
     }
 }
 
 impl DerefMut for NodeList {
-    fn deref_mut(&mut self) -> &mut Vec<RefCell<Node>> {
+    fn deref_mut(&mut self) -> &mut Vec<Node> {
         &mut self.0
     }
 }
@@ -180,16 +179,16 @@ impl NodeList {
             row.chars()
                 .enumerate()
                 .map(|(i, c)| {
-                    RefCell::new(Node::new(
+                    Node::new(
                         c,
                         Coordinate::new(index.try_into().unwrap(), i.try_into().unwrap()),
-                    ))
+                    )
                 })
                 .collect(),
         )
     }
 
-    fn get_column(&self, column: isize) -> Option<&RefCell<Node>> {
+    fn get_column(&self, column: isize) -> Option<&Node> {
         match column.try_into() {
             Ok(ucol) => match self.get::<usize>(ucol) {
                 Some(rc_node) => Some(rc_node),
@@ -205,19 +204,24 @@ struct QueuedVisit {
     from_coordinate: Coordinate,
 }
 
+// Each plane of `visited` marks the cells a beam has entered heading one way.
 #[derive(Debug)]
 struct Grid {
     nodes: Vec<NodeList>,
+    visited: BitPlanes,
 }
 impl FromStr for Grid {
     type Err = ();
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let nodes = s
+            .lines()
+            .enumerate()
+            .map(|(i, l)| NodeList::new(i, l))
+            .collect::<Vec<NodeList>>();
+        let (rows, columns) = (nodes.len(), nodes.first().map_or(0, |nl| nl.len()));
         Ok(Grid {
-            nodes: s
-                .lines()
-                .enumerate()
-                .map(|(i, l)| NodeList::new(i, l))
-                .collect::<Vec<NodeList>>(),
+            nodes,
+            visited: BitPlanes::new(rows, columns, 4),
         })
     }
 }
@@ -232,7 +236,7 @@ impl Grid {
             Err(_) => None,
         }
     }
-    fn get_node(&self, coordinate: Coordinate) -> Option<&RefCell<Node>> {
+    fn get_node(&self, coordinate: Coordinate) -> Option<&Node> {
         let nodelist: Option<&NodeList> = self.get_row(coordinate.row);
         match nodelist?.get_column(coordinate.column) {
             Some(rc_node) => Some(rc_node),
@@ -240,13 +244,28 @@ impl Grid {
         }
     }
 
-    fn reset(&self) {
-        self.nodes
-            .iter()
-            .for_each(|nl| nl.iter().for_each(|rc_node| rc_node.borrow_mut().reset()));
+    fn reset(&mut self) {
+        self.visited.clear();
+    }
+
+    fn walk(&mut self, start_position: Coordinate) {
+        self.walk_with(start_position, |_| ());
     }
 
-    fn walk(&self, start_position: Coordinate) {
+    fn walk_with(&mut self, start_position: Coordinate, on_visit: impl FnMut(&QueuedVisit)) {
+        let mut visited = mem::take(&mut self.visited);
+        self.trace(start_position, &mut visited, on_visit);
+        self.visited = visited;
+    }
+
+    // Calls `on_visit` for every visit that reaches a node from a new direction.
+    // Only reads the grid, so several beams can be traced at once.
+    fn trace(
+        &self,
+        start_position: Coordinate,
+        visited: &mut BitPlanes,
+        mut on_visit: impl FnMut(&QueuedVisit),
+    ) {
         let bounds = self.bounds();
         let first_visit = match start_position.as_tuple() {
             (-1, col) => Coordinate::new(0, col),
@@ -265,12 +284,15 @@ impl Grid {
         while let Some(next_visit) = queue.pop_front() {
             match self.get_node(next_visit.to_coordinate) {
                 Some(next_node) => {
-                    queue.extend(
-                        next_node
-                            .borrow_mut()
-                            .visit(next_visit.from_coordinate)
-                            .into_iter(),
-                    );
+                    let to = next_visit.to_coordinate;
+                    let heading = (to - next_visit.from_coordinate).heading();
+                    let plane = &mut visited.planes_mut()[heading];
+                    if plane.get(to.row as usize, to.column as usize) {
+                        continue;
+                    }
+                    plane.set(to.row as usize, to.column as usize, true);
+                    on_visit(&next_visit);
+                    queue.extend(next_node.visit(next_visit.from_coordinate));
                 }
                 _ => (),
             }
@@ -287,12 +309,7 @@ impl Grid {
     fn count_energized(&mut self, start_pos: Coordinate) -> usize {
         self.reset();
         self.walk(start_pos);
-        self.nodes.iter().fold(0, |acc, nl| {
-            acc + nl
-                .iter()
-                .filter(|rc_node| rc_node.borrow().energized())
-                .count()
-        })
+        self.visited.any().count_ones()
     }
 
     fn perimiter_coords(&self) -> Vec<Coordinate> {
@@ -317,51 +334,102 @@ impl Grid {
         .collect()
     }
 
-    fn find_best(&mut self) -> usize {
-        self.perimiter_coords()
-            .into_iter()
-            .map(|start_pos| self.count_energized(start_pos))
-            .fold(0, |acc, val| acc.max(val))
+    fn find_best(&self) -> usize {
+        let (rows, columns) = self.size();
+        parallel::map(&self.perimiter_coords(), |start_pos| {
+            let mut visited = BitPlanes::new(rows, columns, 4);
+            self.trace(*start_pos, &mut visited, |_| ());
+            visited.any().count_ones()
+        })
+        .into_iter()
+        .fold(0, |acc, val| acc.max(val))
     }
 
     #[allow(dead_code)]
     fn print_energized(&self) {
-        let repr = self
-            .nodes
-            .iter()
-            .map(|nl| {
-                nl.iter()
-                    .map(|rc_node| rc_node.borrow().energized_char())
-                    .collect::<String>()
-            })
-            .collect::<Vec<String>>()
-            .join("\n");
-        println!("{}", repr);
+        print!("{}", self.visited.any());
     }
 }
 
-#[allow(dead_code)]
-fn part1() {
+impl Explorable for Grid {
+    fn size(&self) -> (usize, usize) {
+        let bounds = self.bounds();
+        (bounds.row as usize, bounds.column as usize)
+    }
+
+    fn glyph(&self, row: usize, column: usize) -> char {
+        self.nodes[row][column].optic.char_representation()
+    }
+
+    fn describe(&self, row: usize, column: usize) -> String {
+        let node = &self.nodes[row][column];
+        let mut entered_from = (0..4)
+            .filter(|heading| self.visited.plane(*heading).get(row, column))
+            .map(|heading| (node.index - Coordinate::from_heading(heading)).as_tuple())
+            .collect::<Vec<(isize, isize)>>();
+        entered_from.sort();
+        format!("{:?}, entered from {:?}", node.optic, entered_from)
+    }
+}
+
+fn explore_grid() {
     let input = load_input("part1.txt");
     let mut grid = input
         .parse::<Grid>()
         .expect("Expected to be able to parse the grid...");
+
+    let mut beam = Overlay::new("Beam from (0, -1)");
+    let mut step = 0;
+    grid.walk_with(Coordinate::new(0, -1), |visit| {
+        step += 1;
+        let arrow = match (visit.to_coordinate - visit.from_coordinate).as_tuple() {
+            (-1, 0) => '^',
+            (1, 0) => 'v',
+            (0, -1) => '<',
+            _ => '>',
+        };
+        beam.mark(
+            visit.to_coordinate.row as usize,
+            visit.to_coordinate.column as usize,
+            arrow,
+            format!(
+                "Step {}, entered from {:?}",
+                step,
+                visit.from_coordinate.as_tuple()
+            ),
+        );
+    });
+
+    explore::explore(&grid, &[beam]);
+}
+
+fn part1() {
+    let input = profile::parse(|| load_input("part1.txt"));
+    let mut grid =
+        profile::parse(|| input.parse::<Grid>()).expect("Expected to be able to parse the grid...");
     let result = grid.count_energized(Coordinate::new(0, -1));
+    output::detail("energized", || {
+        grid.visited.any().ones().collect::<Vec<(usize, usize)>>()
+    });
 
-    println!("Part 1 Result: {:?}", result);
+    output::answer(1, result);
 }
 
-#[allow(dead_code)]
 fn part2() {
-    let input = load_input("part1.txt");
-    let mut grid = input
-        .parse::<Grid>()
-        .expect("Expected to be able to parse the grid...");
+    let input = profile::parse(|| load_input("part1.txt"));
+    let grid =
+        profile::parse(|| input.parse::<Grid>()).expect("Expected to be able to parse the grid...");
     let result = grid.find_best();
-    println!("Part 2 Result: {}", result);
+    output::answer(2, result);
 }
 
 fn main() {
-    part1();
-    part2();
+    if explore::requested() {
+        explore_grid();
+        return;
+    }
+
+    profile::part(1, part1);
+    profile::part(2, part2);
+    output::finish();
 }
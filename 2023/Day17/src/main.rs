@@ -1,21 +1,14 @@
+use common::explore::{self, Explorable, Mark, Overlay};
+use common::input::load_input;
+use common::output;
+use common::profile;
 use std::{
     collections::{HashMap, VecDeque},
-    fs::File,
-    io::{BufReader, Read},
     ops::Index,
     str::FromStr,
     usize,
 };
 
-fn load_input(input_name: &str) -> String {
-    let input_file = File::open(format!("inputs/{}", input_name)).unwrap();
-
-    let mut data: String = String::new();
-    let _ = BufReader::new(input_file).read_to_string(&mut data);
-
-    data
-}
-
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 struct GridIndex(usize, usize);
 
@@ -141,6 +134,18 @@ impl Grid {
     }
 
     fn naive_min_path(&self, from: &Node, to: &Node) -> usize {
+        let (min_val, min_path) = self.search(from, to, |_, _| ());
+        println!("New Min Path: {:?}", min_path);
+        min_val
+    }
+
+    // Calls `on_settle` whenever a cell's best known weight is recorded.
+    fn search(
+        &self,
+        from: &Node,
+        to: &Node,
+        mut on_settle: impl FnMut(GridIndex, usize),
+    ) -> (usize, Vec<GridIndex>) {
         let mut step_queue: VecDeque<Step> = VecDeque::new();
         step_queue.push_back(Step {
             state: StepState(from.index(), DirectionCount(Direction::North, 0)),
@@ -172,6 +177,7 @@ impl Grid {
             }
 
             seen.insert(state.0, total_weight);
+            on_settle(state.0, total_weight);
 
             let next_steps = state.1.next_steps();
             step_queue.extend(
@@ -204,8 +210,7 @@ impl Grid {
             );
         }
 
-        println!("New Min Path: {:?}", min_path);
-        min_val
+        (min_val, min_path)
     }
 }
 
@@ -246,10 +251,58 @@ impl Index<GridIndex> for Grid {
     }
 }
 
-#[allow(dead_code)]
-fn part1() {
+impl Explorable for Grid {
+    fn size(&self) -> (usize, usize) {
+        (self.size.0, self.size.1)
+    }
+
+    fn glyph(&self, row: usize, column: usize) -> char {
+        char::from_digit(self[GridIndex(row, column)].1 as u32, 10).unwrap_or('?')
+    }
+
+    fn describe(&self, row: usize, column: usize) -> String {
+        format!("Heat loss {}", self[GridIndex(row, column)].1)
+    }
+}
+
+fn explore_grid() {
     let input = load_input("part1.txt");
     let grid = input.parse::<Grid>().unwrap();
+    let start = grid[GridIndex(0, 0)];
+    let end = grid[grid.size.offset(-1, -1).expect("Should be possible")];
+
+    let mut costs = Overlay::new("Best cost");
+    let (_, min_path) = grid.search(&start, &end, |GridIndex(row, column), weight| {
+        costs.mark(row, column, '*', format!("Best cost so far {}", weight))
+    });
+
+    let mut path = Overlay::new("Min path");
+    let mut total = 0;
+    path.step(
+        min_path
+            .iter()
+            .enumerate()
+            .map(|(i, index)| {
+                if i > 0 {
+                    total += grid[*index].1;
+                }
+                Mark {
+                    row: index.0,
+                    column: index.1,
+                    glyph: '#',
+                    note: format!("Path step {}, cost {}", i, total),
+                }
+            })
+            .collect(),
+    );
+
+    explore::explore(&grid, &[costs, path]);
+}
+
+#[allow(dead_code)]
+fn part1() {
+    let input = profile::parse(|| load_input("part1.txt"));
+    let grid = profile::parse(|| input.parse::<Grid>()).unwrap();
     let start = grid
         .get(GridIndex(0, 0))
         .expect("Should always have a first element.");
@@ -258,17 +311,23 @@ fn part1() {
         .expect("Should be able to find this node");
     let result = grid.naive_min_path(start, end);
 
-    println!("Part 1 Result: {:?}", result);
+    output::answer(1, result);
 }
 
 #[allow(dead_code)]
 fn part2() {
-    let input = load_input("example1.txt");
+    let input = profile::parse(|| load_input("example1.txt"));
     let result = input;
-    println!("Part 2 Result: {}", result);
+    output::answer(2, result);
 }
 
 fn main() {
-    part1();
+    if explore::requested() {
+        explore_grid();
+        return;
+    }
+
+    profile::part(1, part1);
     // part2();
+    output::finish();
 }
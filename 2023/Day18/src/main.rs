@@ -1,17 +1,8 @@
-use std::{
-    fs::File,
-    io::{BufReader, Read},
-    str::FromStr,
-};
-
-fn load_input(input_name: &str) -> String {
-    let input_file = File::open(format!("inputs/{}", input_name)).unwrap();
-
-    let mut data: String = String::new();
-    let _ = BufReader::new(input_file).read_to_string(&mut data);
-
-    data
-}
+use common::checked;
+use common::input::load_input;
+use common::output;
+use common::profile;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 struct Color {
@@ -81,10 +72,10 @@ impl Position {
     }
     fn step(&mut self, direction: Direction, amount: isize) {
         match direction {
-            Direction::Up => self.0 -= amount,
-            Direction::Down => self.0 += amount,
-            Direction::Left => self.1 -= amount,
-            Direction::Right => self.1 += amount,
+            Direction::Up => self.0 = checked::sub(self.0, amount),
+            Direction::Down => self.0 = checked::add(self.0, amount),
+            Direction::Left => self.1 = checked::sub(self.1, amount),
+            Direction::Right => self.1 = checked::add(self.1, amount),
         }
     }
 }
@@ -154,11 +145,20 @@ impl InstructionsState {
         }
     }
 
-    fn get_area(&self, coordinates: Vec<Position>) -> isize {
+    // Twice the area of a lagoon near isize::MAX doesn't fit in an isize.
+    fn get_area(&self, coordinates: Vec<Position>) -> i128 {
         let mut prev: Position = *coordinates.last().unwrap();
-        let mut running_dividend = 0;
+        let mut running_dividend: i128 = 0;
         for coord in coordinates {
-            running_dividend += (coord.row() * prev.column()) - (prev.row() * coord.column());
+            let (row, column) = (coord.row() as i128, coord.column() as i128);
+            let (prev_row, prev_column) = (prev.row() as i128, prev.column() as i128);
+            running_dividend = checked::add(
+                running_dividend,
+                checked::sub(
+                    checked::mul(row, prev_column),
+                    checked::mul(prev_row, column),
+                ),
+            );
             prev = coord;
         }
 
@@ -172,7 +172,7 @@ impl InstructionsState {
         self.cursor.clone()
     }
 
-    fn process_instructions(&mut self, instructions: &Vec<Instruction>) -> isize {
+    fn process_instructions(&mut self, instructions: &Vec<Instruction>) -> i128 {
         let coords = instructions.iter().map(|i| self.get_coordinates(i));
         let directions = instructions
             .iter()
@@ -228,21 +228,22 @@ fn part_2_instructions(fname: &'static str) -> Vec<Instruction> {
 
 #[allow(dead_code)]
 fn part1() {
-    let instructions = part_1_instructions("part1.txt");
+    let instructions = profile::parse(|| part_1_instructions("part1.txt"));
     let mut state = InstructionsState::new();
     let result = state.process_instructions(&instructions);
-    println!("Part 1 Result: {:?}", result);
+    output::answer(1, result);
 }
 
 #[allow(dead_code)]
 fn part2() {
-    let instructions = part_2_instructions("part1.txt");
+    let instructions = profile::parse(|| part_2_instructions("part1.txt"));
     let mut state = InstructionsState::new();
     let result = state.process_instructions(&instructions);
-    println!("Part 2 Result: {:?}", result);
+    output::answer(2, result);
 }
 
 fn main() {
-    part1();
-    part2();
+    profile::part(1, part1);
+    profile::part(2, part2);
+    output::finish();
 }
@@ -1,21 +1,15 @@
+use common::graph::{self, ExportGraph, Highlight};
+use common::input::load_input;
+use common::output;
+use common::profile;
 use regex::Regex;
+use serde::Serialize;
 use std::{
     cmp::Ordering,
     collections::{HashMap, VecDeque},
-    fs::File,
-    io::{BufReader, Read},
     str::FromStr,
 };
 
-fn load_input(input_name: &str) -> String {
-    let input_file = File::open(format!("inputs/{}", input_name)).unwrap();
-
-    let mut data: String = String::new();
-    let _ = BufReader::new(input_file).read_to_string(&mut data);
-
-    data
-}
-
 #[derive(Debug, Clone, Hash)]
 enum PartCategory {
     X,
@@ -85,6 +79,21 @@ struct WorkflowOperation {
 }
 
 impl WorkflowOperation {
+    fn condition(&self) -> String {
+        let category = match self.category {
+            PartCategory::X => "x",
+            PartCategory::M => "m",
+            PartCategory::A => "a",
+            PartCategory::S => "s",
+        };
+        let order = match self.order {
+            Ordering::Less => "<",
+            Ordering::Equal => "=",
+            Ordering::Greater => ">",
+        };
+        format!("{}{}{}", category, order, self.other_value)
+    }
+
     fn inverted_operation(&self) -> (Ordering, u64) {
         match self.order {
             Ordering::Less => (Ordering::Greater, self.other_value.saturating_sub(1)),
@@ -134,7 +143,7 @@ enum WorkflowStep {
     FallbackWorkflow(String),
 }
 
-#[derive(Debug, Clone, Copy, Hash, Eq)]
+#[derive(Debug, Clone, Copy, Hash, Eq, Serialize)]
 struct Range {
     min: u64,
     max: u64,
@@ -187,7 +196,7 @@ impl Range {
     }
 }
 
-#[derive(Debug, Clone, Copy, Hash)]
+#[derive(Debug, Clone, Copy, Hash, Serialize)]
 struct AcceptanceConstraints {
     x: Range,
     m: Range,
@@ -324,12 +333,19 @@ impl System {
     }
 
     fn check_part(&self, part: &Part) -> bool {
-        let mut next_workflow = "in".to_string();
+        self.workflow_path(part).last().is_some_and(|w| w == "A")
+    }
+
+    // Every workflow the part visits, ending in "A" or "R".
+    fn workflow_path(&self, part: &Part) -> Vec<String> {
+        let mut path = vec!["in".to_string()];
         loop {
-            match self.run_workflow(next_workflow, &part) {
-                a if a == "A".to_string() => return true,
-                r if r == "R".to_string() => return false,
-                other => next_workflow = other,
+            match self.run_workflow(path.last().unwrap().clone(), &part) {
+                end if end == "A" || end == "R" => {
+                    path.push(end);
+                    return path;
+                }
+                other => path.push(other),
             }
         }
     }
@@ -339,22 +355,25 @@ impl System {
     }
 
     fn count_combinations(&mut self) -> u64 {
+        self.accepted_constraints().iter().map(|c| c.count()).sum()
+    }
+
+    // The disjoint boxes of ratings that end up accepted.
+    fn accepted_constraints(&self) -> Vec<AcceptanceConstraints> {
         let mut queue: VecDeque<(String, AcceptanceConstraints)> =
             vec![("in".to_string(), AcceptanceConstraints::default())].into();
-        let mut count: u64 = 0;
+        let mut accepted = vec![];
 
         while let Some((workflow, acceptance_criteria)) = queue.pop_front() {
             match workflow {
-                a if a == "A".to_string() => {
-                    count += acceptance_criteria.count();
-                }
+                a if a == "A".to_string() => accepted.push(acceptance_criteria),
                 r if r == "R".to_string() => (),
                 _ => queue
                     .extend(self.workflows[&workflow].acceptance_constraints(&acceptance_criteria)),
             }
         }
 
-        count
+        accepted
     }
 }
 
@@ -378,23 +397,84 @@ impl FromStr for System {
     }
 }
 
-#[allow(dead_code)]
-fn part1() {
+impl ExportGraph for System {
+    fn nodes(&self) -> Vec<graph::Node> {
+        let mut nodes = self
+            .workflows
+            .keys()
+            .map(|name| graph::Node {
+                id: name.clone(),
+                label: name.clone(),
+                kind: "workflow".to_string(),
+            })
+            .collect::<Vec<_>>();
+        nodes.extend(
+            [("A", "accept"), ("R", "reject")].map(|(id, kind)| graph::Node {
+                id: id.to_string(),
+                label: id.to_string(),
+                kind: kind.to_string(),
+            }),
+        );
+        nodes
+    }
+
+    fn edges(&self) -> Vec<graph::Edge> {
+        self.workflows
+            .values()
+            .flat_map(|w| {
+                w.steps.iter().map(|step| {
+                    let (to, label) = match step {
+                        WorkflowStep::Operation(op) => {
+                            (op.success_workflow.clone(), op.condition())
+                        }
+                        WorkflowStep::FallbackWorkflow(to) => (to.clone(), "else".to_string()),
+                    };
+                    graph::Edge {
+                        from: w.name.clone(),
+                        to,
+                        label,
+                    }
+                })
+            })
+            .collect()
+    }
+}
+
+// Highlights the workflows the first part is sent through.
+fn export_graph(format: graph::Format) {
     let input = load_input("part1.txt");
     let system = input.parse::<System>().expect("Expected to have a system.");
+    let highlight = match system.parts.first() {
+        Some(part) => Highlight::path(&system.workflow_path(part)),
+        None => Highlight::default(),
+    };
+    print!("{}", graph::render(&system, format, &highlight));
+}
+
+#[allow(dead_code)]
+fn part1() {
+    let input = profile::parse(|| load_input("part1.txt"));
+    let system = profile::parse(|| input.parse::<System>()).expect("Expected to have a system.");
     let result = system.check_parts();
-    println!("Part 1 Result: {}", result);
+    output::answer(1, result);
 }
 
 #[allow(dead_code)]
 fn part2() {
-    let input = load_input("part1.txt");
-    let mut system = input.parse::<System>().expect("Expected to have a system.");
+    let input = profile::parse(|| load_input("part1.txt"));
+    let mut system =
+        profile::parse(|| input.parse::<System>()).expect("Expected to have a system.");
     let result = system.count_combinations();
-    println!("Part 2 Result: {}", result);
+    output::detail("accepted", || system.accepted_constraints());
+    output::answer(2, result);
 }
 
 fn main() {
-    part1();
-    part2();
+    if let Some(format) = graph::requested() {
+        export_graph(format);
+        return;
+    }
+    profile::part(1, part1);
+    profile::part(2, part2);
+    output::finish();
 }
@@ -1,60 +1,74 @@
+use common::checked;
+use common::input::load_input;
+use common::output;
+use common::params;
+use common::profile;
+use serde::Serialize;
 use std::cmp::max;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
 use std::str::FromStr;
 
-#[derive(Debug, Clone, Copy)]
-struct CubeSet {
-    red: i32,
-    green: i32,
-    blue: i32,
-}
+// Cube counts keyed by colour. A colour that isn't present counts as zero.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+struct CubeSet(BTreeMap<String, u64>);
 
 impl FromStr for CubeSet {
-    type Err = ();
+    type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(CubeSet {
-            red: match s.split(", ").find(|e| e.ends_with("red")) {
-                Some(red) => red
-                    .strip_suffix(" red")
-                    .ok_or(())?
-                    .parse::<i32>()
-                    .expect("Received a malformed Red value."),
-                _ => 0,
-            },
-            green: match s.split(", ").find(|e| e.ends_with("green")) {
-                Some(green) => green
-                    .strip_suffix(" green")
-                    .ok_or(())?
-                    .parse::<i32>()
-                    .expect("Received a malformed Green value."),
-                _ => 0,
-            },
-            blue: match s.split(", ").find(|e| e.ends_with("blue")) {
-                Some(blue) => blue
-                    .strip_suffix(" blue")
-                    .ok_or(())?
-                    .parse::<i32>()
-                    .expect("Received a malformed Blue value."),
-                _ => 0,
-            },
-        })
+        let mut cubes = CubeSet::default();
+        for token in s.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            let (count, colour) = token
+                .split_once(' ')
+                .ok_or(format!("Expected `N colour`, got {:?}", token))?;
+            let count = count
+                .parse::<u64>()
+                .map_err(|_| format!("Received a malformed count in {:?}", token))?;
+            let entry = cubes.0.entry(colour.trim().to_string()).or_insert(0);
+            *entry = checked::add(*entry, count);
+        }
+        Ok(cubes)
     }
 }
 
 impl CubeSet {
-    fn power(&self) -> i32 {
-        self.red * self.green * self.blue
+    fn count(&self, colour: &str) -> u64 {
+        self.0.get(colour).copied().unwrap_or(0)
+    }
+
+    fn colours(&self) -> impl Iterator<Item = &String> {
+        self.0.keys()
+    }
+
+    fn total(&self) -> u64 {
+        checked::sum(self.0.values().copied())
+    }
+
+    fn fits_in(&self, bag: &CubeSet) -> bool {
+        self.0
+            .iter()
+            .all(|(colour, count)| bag.count(colour) >= *count)
+    }
+
+    // Taken over `palette` rather than the colours present, so a colour this
+    // set never saw still zeroes the product.
+    fn power<'a>(&self, palette: impl IntoIterator<Item = &'a String>) -> u64 {
+        palette
+            .into_iter()
+            .fold(1, |acc, colour| checked::mul(acc, self.count(colour)))
     }
 
     fn update_minimums(&mut self, other: &CubeSet) {
-        self.red = max(self.red, other.red);
-        self.green = max(self.green, other.green);
-        self.blue = max(self.blue, other.blue);
+        other.0.iter().for_each(|(colour, count)| {
+            let entry = self.0.entry(colour.clone()).or_insert(0);
+            *entry = max(*entry, *count);
+        });
     }
 }
 
 #[derive(Debug)]
 struct Game {
-    id: i32,
+    id: u64,
     revelations: Vec<CubeSet>,
 }
 
@@ -66,16 +80,16 @@ impl FromStr for Game {
                 let id = game
                     .strip_prefix("Game ")
                     .expect("Trying to parse a malformed game")
-                    .parse::<i32>()
+                    .parse::<u64>()
                     .expect("Couldnt parse malformed game id");
                 let revelations: Vec<CubeSet> = rest
                     .trim()
                     .split(";")
                     .map(|cs| match cs.trim().parse::<CubeSet>() {
                         Ok(cubeset) => cubeset,
-                        Err(_) => {
+                        Err(e) => {
                             println!("Failed to parse cubeset: {:?}", cs);
-                            panic!("Failed to parse cubeset");
+                            panic!("Failed to parse cubeset: {}", e);
                         }
                     })
                     .collect();
@@ -89,17 +103,11 @@ impl FromStr for Game {
 
 impl Game {
     fn possible_with(&self, cubes: &CubeSet) -> bool {
-        self.revelations
-            .iter()
-            .all(|cs| cubes.red >= cs.red && cubes.green >= cs.green && cubes.blue >= cs.blue)
+        self.revelations.iter().all(|cs| cs.fits_in(cubes))
     }
 
     fn min_possible(&self) -> CubeSet {
-        let mut min_set = CubeSet {
-            red: 0,
-            green: 0,
-            blue: 0,
-        };
+        let mut min_set = CubeSet::default();
 
         self.revelations
             .iter()
@@ -109,8 +117,189 @@ impl Game {
     }
 }
 
+impl Display for CubeSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let tokens = self
+            .0
+            .iter()
+            .map(|(colour, count)| format!("{} {}", count, colour))
+            .collect::<Vec<String>>();
+        write!(f, "{}", tokens.join(", "))
+    }
+}
+
+// Every colour seen in any game.
+fn palette(games: &[Game]) -> BTreeSet<String> {
+    games
+        .iter()
+        .flat_map(|g| g.revelations.iter().flat_map(|cs| cs.colours()))
+        .cloned()
+        .collect()
+}
+
+// What the games say about the bag they were drawn from. A bag is consistent
+// when every game is possible with it and, given a budget, it holds no more
+// cubes than that in total.
+#[derive(Debug)]
+struct BagInference<'a> {
+    games: &'a [Game],
+    budget: Option<u64>,
+}
+
+impl<'a> BagInference<'a> {
+    fn new(games: &'a [Game], budget: Option<u64>) -> Self {
+        BagInference { games, budget }
+    }
+
+    fn within_budget(&self, bag: &CubeSet) -> bool {
+        self.budget.is_none_or(|budget| bag.total() <= budget)
+    }
+
+    fn is_consistent(&self, bag: &CubeSet) -> bool {
+        self.within_budget(bag) && self.games.iter().all(|g| g.possible_with(bag))
+    }
+
+    // Every consistent bag holds at least this many of each colour, so no bag
+    // is consistent when this one isn't.
+    fn minimal_bag(&self) -> Option<CubeSet> {
+        let mut minimal = CubeSet::default();
+        self.games
+            .iter()
+            .for_each(|g| minimal.update_minimums(&g.min_possible()));
+        Some(minimal).filter(|bag| self.within_budget(bag))
+    }
+
+    // Consistent bags over the games' colours: the ways to share the spare
+    // budget out among them, C(spare + colours, colours). Unbounded without a
+    // budget, and None too when it won't fit in a u128.
+    fn count_consistent(&self) -> Option<u128> {
+        let minimal = self.minimal_bag()?;
+        let spare = (self.budget? - minimal.total()) as u128;
+        let colours = palette(self.games).len() as u128;
+        (1..=colours).try_fold(1u128, |ways, i| Some(ways.checked_mul(spare + i)? / i))
+    }
+
+    // The cheapest bag for each game is the natural candidate: any bag making
+    // that game possible holds at least as much.
+    fn candidates(&self) -> Vec<CubeSet> {
+        let mut candidates: Vec<CubeSet> = self.games.iter().map(|g| g.min_possible()).collect();
+        candidates.sort_by(|a, b| a.0.cmp(&b.0));
+        candidates.dedup();
+        candidates
+    }
+
+    // Most games first, then fewest cubes. Bags over the budget are dropped.
+    fn rank(&self, candidates: Vec<CubeSet>) -> Vec<(CubeSet, usize)> {
+        let mut ranked: Vec<(CubeSet, usize)> = candidates
+            .into_iter()
+            .filter(|bag| self.within_budget(bag))
+            .map(|bag| {
+                let possible = self.games.iter().filter(|g| g.possible_with(&bag)).count();
+                (bag, possible)
+            })
+            .collect();
+        ranked.sort_by(|(a, a_possible), (b, b_possible)| {
+            b_possible
+                .cmp(a_possible)
+                .then(a.total().cmp(&b.total()))
+                .then(a.0.cmp(&b.0))
+        });
+        ranked
+    }
+
+    fn report(&self, extra: &CubeSet) -> InferenceReport {
+        let mut candidates = self.candidates();
+        candidates.push(extra.clone());
+        InferenceReport {
+            budget: self.budget,
+            minimal: self.minimal_bag(),
+            consistent: self.count_consistent().map(|n| n.to_string()),
+            ranking: self
+                .rank(candidates)
+                .into_iter()
+                .map(|(bag, possible)| RankedBag {
+                    cubes: bag.total(),
+                    bag,
+                    possible,
+                })
+                .collect(),
+        }
+    }
+}
+
+// The count of consistent bags is a string, since it can outgrow JSON's
+// integers.
+#[derive(Debug, Serialize)]
+struct InferenceReport {
+    budget: Option<u64>,
+    minimal: Option<CubeSet>,
+    consistent: Option<String>,
+    ranking: Vec<RankedBag>,
+}
+
+#[derive(Debug, Serialize)]
+struct RankedBag {
+    bag: CubeSet,
+    possible: usize,
+    cubes: u64,
+}
+
+// The bag from the puzzle text; `--param bag=...` or `.aoc.toml` replace it.
+const PUZZLE_BAG: &str = "12 red, 13 green, 14 blue";
+
+fn puzzle_bag() -> CubeSet {
+    params::get("bag", PUZZLE_BAG.to_string())
+        .parse()
+        .unwrap_or_else(|e| panic!("Couldn't parse the bag: {}", e))
+}
+
+fn infer() {
+    let games = load_games();
+    let inference = BagInference::new(&games, params::find("budget"));
+    let bag = puzzle_bag();
+
+    match (inference.minimal_bag(), inference.budget) {
+        (Some(minimal), _) => println!(
+            "Minimal consistent bag: {} ({} cubes)",
+            minimal,
+            minimal.total()
+        ),
+        (None, Some(budget)) => println!("No bag of at most {} cubes is consistent", budget),
+        (None, None) => println!("No games to infer from"),
+    }
+    match (inference.budget, inference.count_consistent()) {
+        (Some(budget), Some(count)) => {
+            println!("{} consistent bags of at most {} cubes", count, budget)
+        }
+        (Some(budget), None) if inference.minimal_bag().is_some() => println!(
+            "Too many consistent bags of at most {} cubes to count",
+            budget
+        ),
+        _ => (),
+    }
+    println!(
+        "Bag {} is {}consistent",
+        bag,
+        match inference.is_consistent(&bag) {
+            true => "",
+            false => "not ",
+        }
+    );
+
+    let mut candidates = inference.candidates();
+    candidates.push(bag);
+    println!("Bags by games possible:");
+    inference
+        .rank(candidates)
+        .iter()
+        .take(params::get("top", 10))
+        .for_each(|(bag, possible)| {
+            println!("  {:>4} games: {} ({} cubes)", possible, bag, bag.total())
+        });
+}
+
 fn load_games() -> Vec<Game> {
-    let input = include_str!("../inputs/part1.txt");
+    let input = load_input("part1.txt");
     input
         .trim()
         .split("\n")
@@ -120,28 +309,33 @@ fn load_games() -> Vec<Game> {
 }
 
 fn part1() {
-    let answer: i32 = load_games()
-        .iter()
-        .filter(|g| {
-            g.possible_with(&CubeSet {
-                red: 12,
-                green: 13,
-                blue: 14,
-            })
-        })
-        .map(|g| g.id)
-        .sum();
+    let games = profile::parse(load_games);
+    let bag = puzzle_bag();
+    let answer: u64 = checked::sum(games.iter().filter(|g| g.possible_with(&bag)).map(|g| g.id));
+    output::detail("bag_inference", || {
+        BagInference::new(&games, params::find("budget")).report(&bag)
+    });
 
-    println!("Part 1 Answer: {}", answer);
+    output::answer(1, answer);
 }
 
 fn part2() {
-    let answer: i32 = load_games().iter().map(|g| g.min_possible().power()).sum();
+    let games = profile::parse(load_games);
+    // Over every colour seen in any game, so a game missing one has no power.
+    let palette = palette(&games);
+    let answer: u64 = checked::sum(games.iter().map(|g| g.min_possible().power(&palette)));
 
-    println!("Part 2 Answer: {}", answer);
+    output::answer(2, answer);
 }
 
 fn main() {
-    part1();
-    part2();
+    // `infer=true` prints what the games reveal about the bag instead of solving.
+    if params::get("infer", false) {
+        infer();
+        return;
+    }
+
+    profile::part(1, part1);
+    profile::part(2, part2);
+    output::finish();
 }
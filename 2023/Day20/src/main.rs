@@ -1,23 +1,22 @@
+use common::checked;
+use common::graph::{self, ExportGraph, Highlight};
+use common::input::load_input;
+use common::output;
+use common::profile;
 use std::{
     borrow::BorrowMut,
     collections::{HashMap, VecDeque},
-    fs::File,
-    io::{BufReader, Read},
     ops::{Deref, DerefMut},
     str::FromStr,
 };
 
-fn load_input(input_name: &str) -> String {
-    let input_file = File::open(format!("inputs/{}", input_name)).unwrap();
-
-    let mut data: String = String::new();
-    let _ = BufReader::new(input_file).read_to_string(&mut data);
-
-    data
-}
-
 trait CommunicationModule {
     fn name(&self) -> String;
+    fn kind(&self) -> &'static str;
+    fn outputs(&self) -> Vec<String> {
+        vec![]
+    }
+
     fn as_emitter(&mut self) -> Option<&mut dyn Emitter> {
         None
     }
@@ -178,6 +177,14 @@ impl CommunicationModule for FlipFlop {
         self.name.clone()
     }
 
+    fn kind(&self) -> &'static str {
+        "flip-flop"
+    }
+
+    fn outputs(&self) -> Vec<String> {
+        self.output.outputs.clone()
+    }
+
     fn as_emitter(&mut self) -> Option<&mut dyn Emitter> {
         Some(self)
     }
@@ -279,6 +286,14 @@ impl CommunicationModule for Conjunction {
         self.name.clone()
     }
 
+    fn kind(&self) -> &'static str {
+        "conjunction"
+    }
+
+    fn outputs(&self) -> Vec<String> {
+        self.output.outputs.clone()
+    }
+
     fn as_emitter(&mut self) -> Option<&mut dyn Emitter> {
         Some(self)
     }
@@ -398,6 +413,14 @@ impl CommunicationModule for Broadcaster {
         "Broadcaster".to_string()
     }
 
+    fn kind(&self) -> &'static str {
+        "broadcaster"
+    }
+
+    fn outputs(&self) -> Vec<String> {
+        self.output.outputs.clone()
+    }
+
     fn as_broadcaster(&mut self) -> Option<&mut Broadcaster> {
         Some(self)
     }
@@ -457,6 +480,10 @@ impl CommunicationModule for OutputSink {
         }
     }
 
+    fn kind(&self) -> &'static str {
+        "sink"
+    }
+
     fn as_receiver(&mut self) -> Option<&mut dyn Receiver> {
         Some(self)
     }
@@ -468,8 +495,8 @@ impl CommunicationModule for OutputSink {
 
 struct Circuit {
     io_modules: HashMap<String, Box<dyn CommunicationModule>>,
-    low_pulse_count: u32,
-    high_pulse_count: u32,
+    low_pulse_count: u64,
+    high_pulse_count: u64,
 }
 
 impl Circuit {
@@ -480,7 +507,7 @@ impl Circuit {
     }
 
     fn press_button(&mut self, times: u32) {
-        self.low_pulse_count += times;
+        self.low_pulse_count = checked::add(self.low_pulse_count, times as u64);
         match self
             .io_modules
             .get_mut("Broadcaster")
@@ -585,8 +612,8 @@ impl Circuit {
 
     fn count_pulse(&mut self, pulse: &Pulse) {
         match pulse {
-            Pulse::High => self.high_pulse_count += 1,
-            Pulse::Low => self.low_pulse_count = self.low_pulse_count.wrapping_add(1),
+            Pulse::High => self.high_pulse_count = checked::add(self.high_pulse_count, 1),
+            Pulse::Low => self.low_pulse_count = checked::add(self.low_pulse_count, 1),
         }
     }
 
@@ -645,6 +672,33 @@ impl Circuit {
     }
 }
 
+// Expects `hookup_inputs` to have run, so every destination has a module.
+impl ExportGraph for Circuit {
+    fn nodes(&self) -> Vec<graph::Node> {
+        self.io_modules
+            .values()
+            .map(|m| graph::Node {
+                id: m.name(),
+                label: m.name(),
+                kind: m.kind().to_string(),
+            })
+            .collect()
+    }
+
+    fn edges(&self) -> Vec<graph::Edge> {
+        self.io_modules
+            .values()
+            .flat_map(|m| {
+                m.outputs().into_iter().map(move |to| graph::Edge {
+                    from: m.name(),
+                    to,
+                    label: String::new(),
+                })
+            })
+            .collect()
+    }
+}
+
 impl FromStr for Circuit {
     type Err = ();
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -697,13 +751,21 @@ impl FromStr for Circuit {
     }
 }
 
-#[allow(dead_code)]
-fn part1() {
+fn export_graph(format: graph::Format) {
     let input = load_input("part1.txt");
     let mut circuit = input
         .parse::<Circuit>()
         .expect("Expected to be able to parse circuit.");
     circuit.hookup_inputs();
+    print!("{}", graph::render(&circuit, format, &Highlight::default()));
+}
+
+#[allow(dead_code)]
+fn part1() {
+    let input = profile::parse(|| load_input("part1.txt"));
+    let mut circuit =
+        profile::parse(|| input.parse::<Circuit>()).expect("Expected to be able to parse circuit.");
+    circuit.hookup_inputs();
     circuit.press_button(1000);
     // circuit.print();
     circuit.clock();
@@ -713,17 +775,16 @@ fn part1() {
         "High: {}, Low: {}",
         circuit.high_pulse_count, circuit.low_pulse_count
     );
-    let result = circuit.high_pulse_count * circuit.low_pulse_count;
+    let result = checked::mul(circuit.high_pulse_count, circuit.low_pulse_count);
 
-    println!("Part 1 Result: {}", result);
+    output::answer(1, result);
 }
 
 #[allow(dead_code)]
 fn part2() {
-    let input = load_input("part1.txt");
-    let mut circuit = input
-        .parse::<Circuit>()
-        .expect("Expected to be able to parse circuit.");
+    let input = profile::parse(|| load_input("part1.txt"));
+    let mut circuit =
+        profile::parse(|| input.parse::<Circuit>()).expect("Expected to be able to parse circuit.");
     circuit.hookup_inputs();
     circuit.press_button(1);
     // circuit.print();
@@ -734,11 +795,16 @@ fn part2() {
         "High: {}, Low: {}",
         circuit.high_pulse_count, circuit.low_pulse_count
     );
-    let result = circuit.high_pulse_count * circuit.low_pulse_count;
-    println!("Part 2 Result: {}", result);
+    let result = checked::mul(circuit.high_pulse_count, circuit.low_pulse_count);
+    output::answer(2, result);
 }
 
 fn main() {
-    part1();
-    part2();
+    if let Some(format) = graph::requested() {
+        export_graph(format);
+        return;
+    }
+    profile::part(1, part1);
+    profile::part(2, part2);
+    output::finish();
 }
@@ -1,23 +1,17 @@
+use common::explore::{self, Explorable, Mark, Overlay};
+use common::input::load_input;
+use common::output;
+use common::params;
+use common::profile;
 use std::{
     cell::RefCell,
     collections::{HashMap, HashSet, VecDeque},
-    fs::File,
-    io::{BufReader, Read},
     ops::Index,
     str::FromStr,
 };
 
 use itertools::Itertools;
 
-fn load_input(input_name: &str) -> String {
-    let input_file = File::open(format!("inputs/{}", input_name)).unwrap();
-
-    let mut data: String = String::new();
-    let _ = BufReader::new(input_file).read_to_string(&mut data);
-
-    data
-}
-
 #[derive(Debug, Hash, Clone, Copy, Eq, PartialEq)]
 struct Position(usize, usize);
 
@@ -175,10 +169,18 @@ impl Map {
     }
 
     fn count_max_positions(&self, steps_allowed: usize) -> usize {
+        let matching_remainder = steps_allowed % 2;
+
+        self.distances(steps_allowed)
+            .iter()
+            .filter(|(_, v)| *v % 2 == matching_remainder)
+            .count()
+    }
+
+    fn distances(&self, steps_allowed: usize) -> HashMap<Position, usize> {
         let mut seen: HashMap<Position, usize> = HashMap::new();
         let mut position_queue: VecDeque<(Position, usize)> = VecDeque::new();
 
-        let matching_remainder = steps_allowed % 2;
         position_queue.push_back((self.starting_position(), 0));
 
         while let Some((position, step_count)) = position_queue.pop_front() {
@@ -198,9 +200,7 @@ impl Map {
             }
         }
 
-        seen.iter()
-            .filter(|(_, v)| *v % 2 == matching_remainder)
-            .count()
+        seen
     }
     fn check_position(&self, p: Position) {
         let val = &self[p];
@@ -239,23 +239,70 @@ impl FromStr for Map {
     }
 }
 
-#[allow(dead_code)]
-fn part1() {
+impl Explorable for Map {
+    fn size(&self) -> (usize, usize) {
+        let bounds = self.bounds();
+        (bounds.0, bounds.1)
+    }
+
+    fn glyph(&self, row: usize, column: usize) -> char {
+        match self.grid[row][column] {
+            Entity::StartingPosition => 'S',
+            Entity::GardenPlot => '.',
+            Entity::Rock => '#',
+        }
+    }
+
+    fn describe(&self, row: usize, column: usize) -> String {
+        format!("{:?}", self.grid[row][column])
+    }
+}
+
+fn explore_map() {
+    let steps_allowed = params::get("steps", 64);
     let input = load_input("part1.txt");
     let map = input
         .parse::<Map>()
         .expect("Map should have been parsed successfully!");
-    let result = map.count_max_positions(64);
+    let distances = map.distances(steps_allowed);
+
+    let mut reached = Overlay::new("Distance from start");
+    (0..=steps_allowed).for_each(|step| {
+        reached.step(
+            distances
+                .iter()
+                .filter(|(_, distance)| **distance == step)
+                .map(|(position, distance)| Mark {
+                    row: position.0,
+                    column: position.1,
+                    glyph: match distance % 2 == steps_allowed % 2 {
+                        true => 'O',
+                        false => 'o',
+                    },
+                    note: format!("Distance {}", distance),
+                })
+                .collect(),
+        )
+    });
+
+    explore::explore(&map, &[reached]);
+}
 
-    println!("Part 1 Result: {:?}", result);
+#[allow(dead_code)]
+fn part1() {
+    let input = profile::parse(|| load_input("part1.txt"));
+    let map =
+        profile::parse(|| input.parse::<Map>()).expect("Map should have been parsed successfully!");
+    let result = map.count_max_positions(params::get("steps", 64));
+
+    output::answer(1, result);
 }
 
 #[allow(dead_code)]
 fn part2() {
-    let input = load_input("part1.txt");
-    let mut map = input
-        .parse::<Map>()
-        .expect("Map should have been parsed successfully!");
+    let input = profile::parse(|| load_input("part1.txt"));
+    let mut map =
+        profile::parse(|| input.parse::<Map>()).expect("Map should have been parsed successfully!");
     // println!("Bounds: {:?}", map.bounds());
     // let test_inputs: Vec<(isize, usize)> = vec![
     //     (6, 16),
@@ -266,7 +313,7 @@ fn part2() {
     //     // (1000, 668697),
     //     // (5000, 16733044),
     // ];
-    let test_inputs = (0..500);
+    let test_inputs = (0..params::get("probe_steps", 500));
     test_inputs
         // .into_iter()
         .for_each(|input| {
@@ -288,6 +335,12 @@ fn part2() {
 }
 
 fn main() {
+    if explore::requested() {
+        explore_map();
+        return;
+    }
+
     // part1();
-    part2();
+    profile::part(2, part2);
+    output::finish();
 }
@@ -1,21 +1,10 @@
-use std::{
-    collections::HashMap,
-    fmt::Display,
-    fs::File,
-    io::{BufReader, Read},
-    str::FromStr,
-};
+use common::input::load_input;
+use common::output;
+use common::profile;
+use serde::Serialize;
+use std::{collections::HashMap, fmt::Display, str::FromStr};
 
-fn load_input(input_name: &str) -> String {
-    let input_file = File::open(format!("inputs/{}", input_name)).unwrap();
-
-    let mut data: String = String::new();
-    let _ = BufReader::new(input_file).read_to_string(&mut data);
-
-    data
-}
-
-#[derive(Debug, Clone, Copy, Eq, PartialOrd)]
+#[derive(Debug, Clone, Copy, Eq, PartialOrd, Serialize)]
 struct Point3d {
     x: u32,
     y: u32,
@@ -69,7 +58,7 @@ impl FromStr for Point3d {
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq)]
+#[derive(Debug, Clone, Copy, Eq, Serialize)]
 struct Brick {
     bottom_corner: Point3d,
     top_corner: Point3d,
@@ -230,7 +219,8 @@ impl BrickBoard {
         // let mut brickset: BTreeSet<Brick> = BTreeSet::new();
         let mut brickset: Vec<Brick> = vec![];
         println!("Bricks: {:?}", bricks);
-        bricks.sort_by(|a, b| a.max_z().cmp(&b.max_z()));
+        // Bricks settle from the ground up, so the lowest has to land first.
+        bricks.sort_by(|a, b| a.min_z().cmp(&b.min_z()));
         let mut tops: HashMap<(u32, u32), u32> = HashMap::new();
         let mut positions: HashMap<(u32, u32, u32), Brick> = HashMap::new();
         for brick in bricks.iter_mut() {
@@ -278,24 +268,28 @@ impl BrickBoard {
         }
     }
 
-    fn what_supports(&self, brick: Brick) -> Vec<Brick> {
-        brick
+    // The distinct bricks in layer `z` under or over the brick's footprint.
+    fn bricks_at_z(&self, brick: &Brick, z: u32) -> Vec<Brick> {
+        let mut bricks = brick
             .xy_points()
             .into_iter()
-            .map(|(x, y)| self.positions[&(x, y, brick.min_z() - 1)])
-            .collect::<Vec<Brick>>()
+            .filter_map(|(x, y)| self.positions.get(&(x, y, z)).copied())
+            .collect::<Vec<Brick>>();
+        bricks.sort();
+        bricks.dedup();
+        bricks
+    }
+
+    fn what_supports(&self, brick: Brick) -> Vec<Brick> {
+        self.bricks_at_z(&brick, brick.min_z() - 1)
     }
 
     fn this_supports_what(&self, brick: Brick) -> Vec<Brick> {
-        brick
-            .xy_points()
-            .into_iter()
-            .map(|(x, y)| self.positions[&(x, y, brick.max_z() + 1)])
-            .collect::<Vec<Brick>>()
+        self.bricks_at_z(&brick, brick.max_z() + 1)
     }
 
     fn supporting_brick(&self, brick: &Brick) -> Option<Brick> {
-        let supports = self.bricks_at_z(brick.min_z() - 1, |br| br.intersects(brick));
+        let supports = self.what_supports(*brick);
 
         // let supports = self
         //     .bricks
@@ -310,29 +304,29 @@ impl BrickBoard {
         match supports.as_slice() {
             [s] => {
                 println!("Sole Support: {} for {}", s, brick);
-                Some(**s)
+                Some(*s)
             }
             _ => None,
         }
     }
 
+    // A brick can go when nothing resting on it is held up by it alone.
     fn count_removable(&self) -> usize {
-        self.bricks.iter().filter(|brick| {
-            self.this_supports_what(brick).into_iter().map(|brick_s| {
-                self.what_supports(brick_s)
+        self.bricks
+            .iter()
+            .filter(|brick| {
+                self.this_supports_what(**brick)
                     .iter()
-                    .all(|brick_s_s| *brick_s_s == brick)
+                    .all(|above| self.supporting_brick(above) != Some(**brick))
             })
-        })
+            .count()
     }
 }
 
-#[allow(dead_code)]
 fn part1() {
-    let input = load_input("part1.txt");
-    // let input = load_input("part1.txt");
-    let board = input
-        .parse::<BrickBoard>()
+    let input = profile::parse(|| load_input("part1.txt"));
+    // let input = profile::parse(|| load_input("part1.txt"));
+    let board = profile::parse(|| input.parse::<BrickBoard>())
         .expect("Expected to parse all the bricks...");
     // let bricks_dup = board.bricks.clone();
     // board.settle_bricks();
@@ -342,18 +336,20 @@ fn part1() {
     //     .zip(bricks_dup.iter())
     //     .for_each(|(b1, b2)| println!("{} {}", b1, b2));
 
+    output::detail("settled", || board.bricks.clone());
     let result = board.count_removable();
-    println!("Part 1 Result: {:?}", result);
+    output::answer(1, result);
 }
 
 #[allow(dead_code)]
 fn part2() {
-    let input = load_input("example1.txt");
+    let input = profile::parse(|| load_input("example1.txt"));
     let result = input;
-    println!("Part 2 Result: {}", result);
+    output::answer(2, result);
 }
 
 fn main() {
-    part1();
+    profile::part(1, part1);
     // part2();
+    output::finish();
 }
@@ -1,19 +1,8 @@
-use std::{
-    collections::HashSet,
-    fs::File,
-    io::{BufReader, Read},
-    ops::Index,
-    str::FromStr,
-};
-
-fn load_input(input_name: &str) -> String {
-    let input_file = File::open(format!("inputs/{}", input_name)).unwrap();
-
-    let mut data: String = String::new();
-    let _ = BufReader::new(input_file).read_to_string(&mut data);
-
-    data
-}
+use common::explore::{self, Explorable, Mark, Overlay};
+use common::input::load_input;
+use common::output;
+use common::profile;
+use std::{collections::HashSet, ops::Index, str::FromStr};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Direction {
@@ -207,6 +196,21 @@ impl Map {
         MapIndex(self.tiles.len() - 1, end_col)
     }
 
+    // Start and end count as junctions so every hike runs between two of them.
+    fn junctions(&self) -> Vec<MapIndex> {
+        let start = self.starting_position();
+        let end = self.ending_position();
+        self.tiles
+            .iter()
+            .flatten()
+            .filter(|node| node.tile != Tile::Forest)
+            .map(|node| node.index)
+            .filter(|index| {
+                *index == start || *index == end || self.adjacent_path_nodes(index).len() >= 3
+            })
+            .collect()
+    }
+
     fn print_path(&self, path: &Path) {
         println!(
             "{}",
@@ -304,26 +308,78 @@ impl Path {
     }
 }
 
-#[allow(dead_code)]
-fn part1() {
+impl Explorable for Map {
+    fn size(&self) -> (usize, usize) {
+        (self.tiles.len(), self.tiles[0].len())
+    }
+
+    fn glyph(&self, row: usize, column: usize) -> char {
+        match self.tiles[row][column].tile {
+            Tile::Path => '.',
+            Tile::Forest => '#',
+            Tile::Slope(Direction::Up) => '^',
+            Tile::Slope(Direction::Down) => 'v',
+            Tile::Slope(Direction::Left) => '<',
+            Tile::Slope(Direction::Right) => '>',
+        }
+    }
+
+    fn describe(&self, row: usize, column: usize) -> String {
+        format!("{:?}", self.tiles[row][column].tile)
+    }
+}
+
+fn explore_map() {
     let input = load_input("part1.txt");
     let map = input.parse::<Map>().unwrap();
+
+    let mut junctions = Overlay::new("Junctions");
+    junctions.step(
+        map.junctions()
+            .iter()
+            .enumerate()
+            .map(|(id, index)| Mark {
+                row: index.0,
+                column: index.1,
+                glyph: char::from_digit(id as u32, 36).unwrap_or('@'),
+                note: format!(
+                    "Junction {}, {} exits",
+                    id,
+                    map.adjacent_path_nodes(index).len()
+                ),
+            })
+            .collect(),
+    );
+
+    explore::explore(&map, &[junctions]);
+}
+
+#[allow(dead_code)]
+fn part1() {
+    let input = profile::parse(|| load_input("part1.txt"));
+    let map = profile::parse(|| input.parse::<Map>()).unwrap();
     let result = map.find_farthest_hike();
 
-    println!("Part 1 Result: {}", result);
+    output::answer(1, result);
 }
 
 #[allow(dead_code)]
 fn part2() {
-    let input = load_input("part1.txt");
-    let mut map = input.parse::<Map>().unwrap();
+    let input = profile::parse(|| load_input("part1.txt"));
+    let mut map = profile::parse(|| input.parse::<Map>()).unwrap();
     map.solution = SolutionType::Part2;
     let result = map.find_farthest_hike();
     println!("Longest: {:?}", result);
-    println!("Part 2 Result: {}", result);
+    output::answer(2, result);
 }
 
 fn main() {
+    if explore::requested() {
+        explore_map();
+        return;
+    }
+
     // part1();
-    part2();
+    profile::part(2, part2);
+    output::finish();
 }
@@ -1,315 +1,31 @@
-use itertools::{self, Itertools};
-use std::{
-    collections::HashSet,
-    fs::File,
-    io::{BufReader, Read},
-    ops::{RangeBounds, RangeInclusive},
-    str::FromStr,
-};
-
-fn load_input(input_name: &str) -> String {
-    let input_file = File::open(format!("inputs/{}", input_name)).unwrap();
-
-    let mut data: String = String::new();
-    let _ = BufReader::new(input_file).read_to_string(&mut data);
-
-    data
-}
-
-#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
-struct HashablePosition {
-    x: (u64, u64),
-    y: (u64, u64),
-    z: (u64, u64),
-}
-
-#[derive(Debug, Clone, Copy, PartialEq)]
-struct Position {
-    x: f64,
-    y: f64,
-    z: f64,
-}
-impl FromStr for Position {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s
-            .trim()
-            .split(", ")
-            .map(|v| v.trim().parse::<f64>().expect("Position component"))
-            .into_iter()
-            .collect::<Vec<_>>()
-            .as_slice()
-        {
-            [x, y, z] => Ok(Position {
-                x: *x,
-                y: *y,
-                z: *z,
-            }),
-            _ => Err(()),
-        }
-    }
-}
-
-impl Position {
-    fn hashable_position(&self) -> HashablePosition {
-        HashablePosition {
-            x: (self.x as u64, (self.x.fract() * 1000.0) as u64),
-            y: (self.y as u64, (self.y.fract() * 1000.0) as u64),
-            z: (self.z as u64, (self.z.fract() * 1000.0) as u64),
-        }
-    }
-    fn offset_2d(&self, delta_x: f64, delta_y: f64) -> Position {
-        Position {
-            x: self.x + delta_x,
-            y: self.y + delta_y,
-            z: self.z,
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-struct Velocity {
-    delta_x: f64,
-    delta_y: f64,
-    delta_z: f64,
-}
-
-impl FromStr for Velocity {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s
-            .trim()
-            .split(", ")
-            .map(|v| v.trim().parse::<f64>().expect("Velocity component"))
-            .into_iter()
-            .collect::<Vec<_>>()
-            .as_slice()
-        {
-            [dx, dy, dz] => Ok(Velocity {
-                delta_x: *dx,
-                delta_y: *dy,
-                delta_z: *dz,
-            }),
-            _ => Err(()),
-        }
-    }
-}
-
-#[derive(PartialEq)]
-enum Orientation {
-    Colinear,
-    Clockwise,
-    Counterclockwise,
-}
-
-impl Orientation {
-    fn for_points(p1: &Position, p2: &Position, p3: &Position) -> Orientation {
-        match ((p2.y - p1.y) * (p3.x - p2.x)) - ((p2.x - p1.x) * (p3.y - p2.y)) {
-            val if val > 0.0 => Orientation::Clockwise,
-            val if val < 0.0 => Orientation::Counterclockwise,
-            _ => Orientation::Colinear,
-        }
-    }
-}
-
-#[derive(Debug, Clone)]
-struct PathSegment {
-    start: Position,
-    end: Position,
-}
-
-impl PathSegment {
-    fn from(start: Position, end: Position) -> Self {
-        Self { start, end }
-    }
-    fn intersects(&self, other: &PathSegment) -> bool {
-        let o1 = Orientation::for_points(&self.start, &self.end, &other.start);
-        let o2 = Orientation::for_points(&self.start, &self.end, &other.end);
-        let o3 = Orientation::for_points(&other.start, &other.end, &self.start);
-        let o4 = Orientation::for_points(&other.start, &other.end, &self.end);
-
-        if o1 != o2 && o3 != o4 {
-            return true;
-        }
-        match (o1, o2, o3, o4) {
-            (Orientation::Colinear, _, _, _) => self.contains(&other.start),
-            (_, Orientation::Colinear, _, _) => self.contains(&other.end),
-            (_, _, Orientation::Colinear, _) => other.contains(&self.start),
-            (_, _, _, Orientation::Colinear) => other.contains(&self.end),
-            _ => false,
-        }
-    }
-
-    fn contains(&self, point: &Position) -> bool {
-        point.x <= self.start.x.max(self.end.x)
-            && point.x >= self.start.x.min(self.end.x)
-            && point.y <= self.start.y.max(self.end.y)
-            && point.y >= self.start.y.min(self.end.y)
-    }
-}
+use common::geometry::{rational, Line, Rational, Vec3};
+use common::input::load_input;
+use common::linear::{Solution, System};
+use common::output;
+use common::parallel;
+use common::params;
+use common::profile;
+use std::str::FromStr;
 
 #[derive(Debug, Clone)]
 struct HailStone {
-    position: Position,
-    velocity: Velocity,
+    position: Vec3<i64>,
+    velocity: Vec3<i64>,
 }
 
 impl HailStone {
-    fn time_to_x(&self, x: f64) -> Option<f64> {
-        match self.velocity.delta_x {
-            dx if dx == 0.0 => match self.position.x {
-                current_x if current_x == x => Some(0.0),
-                _ => None,
-            },
-            _ => Some((x - self.position.x) / self.velocity.delta_x),
-        }
-    }
-
-    fn time_to_y(&self, y: f64) -> Option<f64> {
-        match self.velocity.delta_y {
-            dy if dy == 0.0 => match self.position.y {
-                current_y if current_y == y => Some(0.0),
-                _ => None,
-            },
-            _ => Some((y - self.position.y) / self.velocity.delta_y),
-        }
+    fn path(&self) -> Line {
+        Line::new(self.position.to_rational(), self.velocity.to_rational())
     }
 
-    fn position_at_time(&self, time: f64) -> Position {
-        self.position
-            .offset_2d(self.velocity.delta_x * time, self.velocity.delta_y * time)
-    }
-
-    fn position_when_x(&self, x: f64) -> Option<Position> {
-        if self.velocity.delta_x == 0.0 {
-            return match x == self.position.x {
-                true => Some(self.position),
-                false => None
-            };
-        }
-
-        let m = self.velocity.delta_y / self.velocity.delta_x;
-        let b = self.position.y - (m * self.position.x);
-        let y = (m * x) + b;
-
-        Some(Position{ x, y, z: self.position.z})
-    }
-
-    fn position_when_y(&self, y: f64) -> Option<Position> {
-        if self.velocity.delta_x == 0.0 {
-            return match self.velocity.delta_y {
-                dy if dy == 0.0 => None,
-                _ => Some(Position{x: self.position.x, y, z:self.position.z})
-            };
-        }
-
-        let m = self.velocity.delta_y / self.velocity.delta_x;
-        let b = self.position.y - (m * self.position.x);
-        if m != 0.0 {
-            let x = (y - b) / m;
-            Some(Position{ x, y, z: self.position.z})
-        } else {
-            match y == self.position.y {
-                true => Some(self.position),
-                false => None
-            }
-        }
-    }
-
-    fn time_to_position(&self, position: &Position) -> Option<f64> {
-        match (self.time_to_x(position.x), self.time_to_y(position.y)) {
-            (Some(t1), Some(t2)) if t1.trunc() == t2.trunc() => Some(t1),
-            (Some(t1), Some(t2)) => {
-                println!("Same?: ({} rounded: {}) == ({} rounded: {}), {:?}, {:?}",t1,  (t1 * 100.0).trunc(), t2, (t2 * 100.0).trunc(), self.position_at_time(t1), self.position_at_time(t2));
-                None
-            }
+    // Paths crossing in the past, or moving in parallel, never meet.
+    fn future_crossing_2d(&self, other: &HailStone) -> Option<Vec3<Rational>> {
+        let (path, other_path) = (self.path().flattened(), other.path().flattened());
+        match path.crossing(&other_path) {
+            Some((t1, t2)) if t1 >= rational(0) && t2 >= rational(0) => Some(path.at(&t1)),
             _ => None,
         }
     }
-
-    fn path_in_window(&self, window: RangeInclusive<isize>) -> Option<PathSegment> {
-        let (start, end) = (*window.start() as f64, *window.end() as f64);
-        let window_verts = [
-            Position {
-                x: start,
-                y: start,
-                z: self.position.z,
-            }, // top left
-            Position {
-                x: end,
-                y: start,
-                z: self.position.z,
-            }, // top right
-            Position {
-                x: end,
-                y: end,
-                z: self.position.z,
-            }, // borrom right
-            Position {
-                x: start,
-                y: end,
-                z: self.position.z,
-            }, // bottom left
-        ];
-        let top = PathSegment::from(window_verts[0].clone(), window_verts[1].clone());
-        let bottom = PathSegment::from(window_verts[3].clone(), window_verts[2].clone());
-        let left = PathSegment::from(window_verts[0].clone(), window_verts[3].clone());
-        let right = PathSegment::from(window_verts[1].clone(), window_verts[2].clone());
-
-        let possible_intersections = [
-            self.position_when_x(start),
-            self.position_when_x(end),
-            self.position_when_y(start),
-            self.position_when_y(end),
-        ];
-        let intersections = possible_intersections
-            .iter()
-            .filter_map(|opos| match opos {
-                Some(position) => {
-                    // println!(
-                    //     "Possible Intersections For: {:?} <Velocity: {:?}>: {:?}",
-                    //     self.position, self.velocity, position
-                    // );
-                    if [&top, &bottom, &left, &right]
-                        .iter()
-                        .find(|bound| bound.contains(position))
-                        .is_some()
-                    {
-                        Some(position)
-                    } else {
-                        None
-                    }
-                }
-                _ => None,
-            })
-            .unique_by(|pos| pos.hashable_position())
-            .collect::<Vec<_>>();
-
-        match intersections.into_iter().collect::<Vec<_>>().as_slice() {
-            [p1, p2] => match (self.time_to_position(*p1), self.time_to_position(*p2)) {
-                (Some(t1), Some(t2)) if t1 >= 0.0 && t2 >= 0.0 => {
-                    Some(PathSegment::from(**p1, **p2))
-                }
-                (Some(t1), Some(t2)) if t1 >= 0.0 && t2 < 0.0 => {
-                    Some(PathSegment::from(self.position.clone(), **p1))
-                }
-                (Some(t1), Some(t2)) if t1 < 0.0 && t2 >= 0.0 => {
-                    Some(PathSegment::from(self.position.clone(), **p2))
-                }
-                (Some(t1), Some(t2)) if t1 < 0.0 && t2 < 0.0 => None,
-                vals => {
-                    println!("Hmm... {:?}, ({:?}, {:?})", vals, p1, p2);
-                    panic!("Should always fall into one of the previous cases.");
-                }
-            },
-            [p1] => Some(PathSegment::from(**p1, **p1)),
-            [] => None,
-            other => {
-                println!("Other: {:?}", other);
-                panic!("Should only have 1, 2, or 0 unique elements here.")
-            },
-        }
-    }
 }
 
 impl FromStr for HailStone {
@@ -317,47 +33,125 @@ impl FromStr for HailStone {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.split_once(" @ ") {
             Some((pos_str, velo_str)) => Ok(HailStone {
-                position: pos_str.parse::<Position>().expect("Position"),
-                velocity: velo_str.parse::<Velocity>().expect("Velocity"),
+                position: pos_str.parse::<Vec3<i64>>().expect("Position"),
+                velocity: velo_str.parse::<Vec3<i64>>().expect("Velocity"),
             }),
             _ => Err(()),
         }
     }
 }
 
-#[allow(dead_code)]
 fn part1() {
     // let (input, window) = (load_input("example1.txt"), 7..=27);
-    let (input, window) = (load_input("part1.txt"), 200000000000000..=400000000000000);
-    let result = input
-        .lines()
-        .map(|l| l.parse::<HailStone>().expect("Hailstone"))
-        .filter_map(|hs| {
-            let path = hs.path_in_window(window.clone());
-            println!("Window Path: {:?}", path);
-            path
-        })
-        .combinations(2)
-        .filter(|paths| match paths.as_slice() {
-            [p1, p2] => p1.intersects(p2),
-            _ => panic!("Should always have 2 paths."),
-        })
-        .count();
-
-    println!("Part 1 Result: {:?}", result);
+    let (input, window) = (
+        load_input("part1.txt"),
+        params::get("test_area_min", 200000000000000)
+            ..=params::get("test_area_max", 400000000000000),
+    );
+    let hailstones = profile::parse(|| {
+        input
+            .lines()
+            .map(|l| l.parse::<HailStone>().expect("Hailstone"))
+            .collect::<Vec<_>>()
+    });
+    let (low, high) = (rational(*window.start()), rational(*window.end()));
+    // Each hailstone checks the ones after it, so every pair is counted once.
+    let result: usize = parallel::map_range(0..hailstones.len(), |i| {
+        hailstones[i + 1..]
+            .iter()
+            .filter(|hs2| match hailstones[i].future_crossing_2d(hs2) {
+                Some(crossing) => {
+                    crossing.x >= low
+                        && crossing.x <= high
+                        && crossing.y >= low
+                        && crossing.y <= high
+                }
+                None => false,
+            })
+            .count()
+    })
+    .into_iter()
+    .sum();
+
+    output::answer(1, result);
+}
+
+// A rock thrown from P with velocity V hits hailstone i when (P - p_i) x (V - v_i) = 0.
+// Subtracting that equation for two hailstones cancels the P x V term, leaving
+// P x (v_j - v_i) + (p_j - p_i) x V = p_j x v_j - p_i x v_i, which is linear in P and V.
+fn rock_equations(system: &mut System, hs1: &HailStone, hs2: &HailStone) {
+    let (p1, v1) = (hs1.position.to_rational(), hs1.velocity.to_rational());
+    let (p2, v2) = (hs2.position.to_rational(), hs2.velocity.to_rational());
+    let w = v2.clone() - v1.clone();
+    let u = p2.clone() - p1.clone();
+    let constant = p2.cross(&v2) - p1.cross(&v1);
+    let zero = rational(0);
+
+    system.equation(
+        vec![
+            zero.clone(),
+            w.z.clone(),
+            -w.y.clone(),
+            zero.clone(),
+            -u.z.clone(),
+            u.y.clone(),
+        ],
+        constant.x,
+    );
+    system.equation(
+        vec![
+            -w.z.clone(),
+            zero.clone(),
+            w.x.clone(),
+            u.z.clone(),
+            zero.clone(),
+            -u.x.clone(),
+        ],
+        constant.y,
+    );
+    system.equation(
+        vec![
+            w.y.clone(),
+            -w.x.clone(),
+            zero.clone(),
+            -u.y.clone(),
+            u.x.clone(),
+            zero,
+        ],
+        constant.z,
+    );
 }
 
-#[allow(dead_code)]
 fn part2() {
-    let input = load_input("example1.txt");
-    let result = input
-        .lines()
-        .map(|l| l.parse::<HailStone>().expect("Hailstone"))
-        .collect::<Vec<_>>();
-    println!("Part 2 Result: {:?}", result);
+    let input = profile::parse(|| load_input("part1.txt"));
+    let hailstones = profile::parse(|| {
+        input
+            .lines()
+            .map(|l| l.parse::<HailStone>().expect("Hailstone"))
+            .collect::<Vec<_>>()
+    });
+
+    // Two pairs pin down the six unknowns; a third guards against degenerate picks.
+    let mut system = System::new(6);
+    hailstones
+        .iter()
+        .skip(1)
+        .take(3)
+        .for_each(|hs| rock_equations(&mut system, &hailstones[0], hs));
+
+    match system.solve() {
+        Solution::Unique(values) => {
+            let position = Vec3::new(values[0].clone(), values[1].clone(), values[2].clone());
+            let velocity = Vec3::new(values[3].clone(), values[4].clone(), values[5].clone());
+            println!("Rock: {} @ {}", position, velocity);
+            output::answer(2, position.x + position.y + position.z);
+        }
+        other => println!("Couldn't find the rock: {}", other),
+    }
 }
 
 fn main() {
-    part1();
-    // part2();
+    profile::part(1, part1);
+    profile::part(2, part2);
+    output::finish();
 }
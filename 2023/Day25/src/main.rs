@@ -1,3 +1,7 @@
+use common::graph::{self, ExportGraph, Highlight};
+use common::input::load_input;
+use common::output;
+use common::profile;
 use eframe::{run_native, App, CreationContext};
 use egui::Context;
 use egui_graphs::{DefaultEdgeShape, DefaultNodeShape, GraphView};
@@ -8,22 +12,13 @@ use petgraph::{
     stable_graph::{DefaultIx, StableGraph, StableUnGraph},
     Undirected,
 };
+use serde::Serialize;
 use std::cell::RefCell;
 use std::{
-    collections::{HashMap, HashSet},
-    fs::File,
-    io::{BufReader, Read},
+    collections::{BTreeSet, HashMap, HashSet},
     str::FromStr,
 };
 
-fn load_input(input_name: &str) -> String {
-    let input_file = File::open(format!("inputs/{}", input_name)).unwrap();
-
-    let mut data: String = String::new();
-    let _ = BufReader::new(input_file).read_to_string(&mut data);
-
-    data
-}
 type NodeId = String;
 
 #[derive(Debug)]
@@ -81,7 +76,7 @@ impl GraphPartition {
             GraphPartitionGroup::GroupB => (&mut self.group_b, &mut self.group_a),
         };
 
-        println!(
+        eprintln!(
             "Moving Node {} to {:?} (inc {}, dec {}) {}",
             node, to_group, cross_inc, cross_dec, self.num_crossreferences
         );
@@ -115,7 +110,7 @@ impl GraphPartition {
                     .or_insert((inc, dec));
             });
 
-        println!("Cross Map: {:?}", cross_map);
+        eprintln!("Cross Map: {:?}", cross_map);
         cross_map
     }
 
@@ -126,7 +121,8 @@ impl GraphPartition {
                 Some((acc_node, (acc_inc, acc_dec))) => {
                     let net_acc = acc_inc - acc_dec;
                     let net_next = next_inc - next_dec;
-                    if net_next < net_acc {
+                    // Ties go to the first name so every run moves the same node.
+                    if net_next < net_acc || (net_next == net_acc && next_node < acc_node) {
                         Some((next_node, (next_inc, next_dec)))
                     } else {
                         Some((acc_node, (acc_inc, acc_dec)))
@@ -137,48 +133,79 @@ impl GraphPartition {
         )
     }
 
-    fn find_partitions(&mut self) -> Result<usize, String> {
-        let first = self
-            .graph
-            .borrow()
-            .nodes
+    fn cut_edges(&self) -> Vec<(NodeId, NodeId)> {
+        self.group_a
             .iter()
-            .map(|(n, e)| (n.to_string(), e.len()))
-            .reduce(
-                |(curr_n, curr_e), (n, e)| {
-                    if e > curr_e {
-                        (n, e)
-                    } else {
-                        (curr_n, curr_e)
-                    }
-                },
-            )
-            .expect("Node to exist");
+            .flat_map(|a| {
+                self.graph
+                    .borrow()
+                    .edges(a)
+                    .into_iter()
+                    .filter(|b| self.group_b.contains(b))
+                    .map(|b| (a.clone(), b))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
 
-        self.move_node(first.0.to_string(), first.1, 0, GraphPartitionGroup::GroupB);
-        let init_edges = self.graph.borrow().edges(&first.0);
+    // Grows group B greedily from `start` and its neighbours until exactly
+    // three wires cross. Returns whether it got there.
+    fn grow_from(&mut self, start: &NodeId) -> bool {
+        self.group_a.extend(self.group_b.drain());
+        self.num_crossreferences = 0;
+
+        let init_edges = self.graph.borrow().edges(start);
+        self.move_node(
+            start.to_string(),
+            init_edges.len(),
+            0,
+            GraphPartitionGroup::GroupB,
+        );
         for node in init_edges.iter() {
-            self.move_node(node.to_string(), GraphPartitionGroup::GroupB);
-        }
-        println!("{:?}\n{:?}", self.group_a, self.group_b);
-
-        while let Some((next_node, (next_inc, next_dec))) = self.next_node_to_move() {
+            let (inc, dec) = self.cross_references()[node];
             self.move_node(
-                next_node,
-                next_inc as usize,
-                next_dec as usize,
+                node.to_string(),
+                inc as usize,
+                dec as usize,
                 GraphPartitionGroup::GroupB,
             );
-
-            if self.num_crossreferences == 3 {
-                break;
+        }
+        eprintln!("{:?}\n{:?}", self.group_a, self.group_b);
+
+        // Moving the first node's neighbours can already leave three wires
+        // crossing, so the count is checked before every move.
+        while self.num_crossreferences != 3 {
+            match self.next_node_to_move() {
+                Some((next_node, (next_inc, next_dec))) => self.move_node(
+                    next_node,
+                    next_inc as usize,
+                    next_dec as usize,
+                    GraphPartitionGroup::GroupB,
+                ),
+                None => break,
             }
         }
+        self.num_crossreferences == 3 && !self.group_a.is_empty()
+    }
+
+    // The greedy growth can miss from a poor start, so every node is tried,
+    // best connected first.
+    fn find_partitions(&mut self) -> Result<usize, String> {
+        let mut starts = self
+            .graph
+            .borrow()
+            .nodes
+            .iter()
+            .map(|(n, e)| (n.to_string(), e.len()))
+            .collect::<Vec<(NodeId, usize)>>();
+        starts.sort_by(|(a, a_edges), (b, b_edges)| b_edges.cmp(a_edges).then(a.cmp(b)));
 
-        match self.num_crossreferences {
-            3 => Ok(self.group_a.len() * self.group_b.len()),
-            _ => Err("Couldnt find valid partition...".to_string()),
+        for (start, _) in starts {
+            if self.grow_from(&start) {
+                return Ok(self.group_a.len() * self.group_b.len());
+            }
         }
+        Err("Couldnt find valid partition...".to_string())
     }
 }
 
@@ -273,6 +300,36 @@ impl Graph {
     }
 }
 
+impl ExportGraph for Graph {
+    fn nodes(&self) -> Vec<graph::Node> {
+        self.nodes
+            .keys()
+            .map(|n| graph::Node {
+                id: n.clone(),
+                label: n.clone(),
+                kind: "component".to_string(),
+            })
+            .collect()
+    }
+
+    fn edges(&self) -> Vec<graph::Edge> {
+        self.nodes
+            .iter()
+            .flat_map(|(n, neighbors)| {
+                neighbors.iter().map(|m| graph::Edge {
+                    from: n.clone(),
+                    to: m.clone(),
+                    label: String::new(),
+                })
+            })
+            .collect()
+    }
+
+    fn directed(&self) -> bool {
+        false
+    }
+}
+
 struct GraphApp {
     g: EGraph<String, (), Undirected>,
 }
@@ -313,15 +370,42 @@ fn show_graph(graph: StableGraph<String, (), Undirected>) {
     .unwrap();
 }
 
-#[allow(dead_code)]
-fn part1() {
-    let input = load_input("example1.txt");
+// Highlights the three wires to cut when a partition is found.
+fn export_graph(format: graph::Format) {
+    let input = load_input("part1.txt");
     let graph = input.parse::<Graph>().expect("Graph");
     let mut partition = GraphPartition::new(graph);
+    let highlight = match partition.find_partitions() {
+        Ok(_) => Highlight::cut(&partition.cut_edges()),
+        Err(_) => Highlight::default(),
+    };
+    print!(
+        "{}",
+        graph::render(&*partition.graph.borrow(), format, &highlight)
+    );
+}
+
+// Sorted so the same partition always reads the same way.
+#[derive(Debug, Serialize)]
+struct PartitionReport {
+    a: BTreeSet<NodeId>,
+    b: BTreeSet<NodeId>,
+    cut: BTreeSet<(NodeId, NodeId)>,
+}
+
+fn part1() {
+    let input = profile::parse(|| load_input("part1.txt"));
+    let graph = profile::parse(|| input.parse::<Graph>()).expect("Graph");
+    let mut partition = GraphPartition::new(graph);
     match partition.find_partitions() {
-        Ok(result) => println!("Part 1 result: {}", result),
+        Ok(result) => output::answer(1, result),
         Err(e) => println!("Part 1 failed: {}", e),
     }
+    output::detail("partition", || PartitionReport {
+        a: partition.group_a.iter().cloned().collect(),
+        b: partition.group_b.iter().cloned().collect(),
+        cut: partition.cut_edges().into_iter().collect(),
+    });
     // show_graph(graph.generate_stable_graph());
     // let result = graph.find_partition(3).expect("Couldn't find partition..");
 
@@ -330,12 +414,17 @@ fn part1() {
 
 #[allow(dead_code)]
 fn part2() {
-    let input = load_input("example1.txt");
+    let input = profile::parse(|| load_input("example1.txt"));
     let result = input;
-    println!("Part 2 Result: {}", result);
+    output::answer(2, result);
 }
 
 fn main() {
-    part1();
+    if let Some(format) = graph::requested() {
+        export_graph(format);
+        return;
+    }
+    profile::part(1, part1);
     // part2();
+    output::finish();
 }
@@ -1,3 +1,9 @@
+use common::input::load_input;
+use common::output;
+use common::params;
+use common::profile;
+use common::render::{self, Canvas, Style};
+use std::collections::{BTreeSet, HashMap};
 use std::ops::Range;
 
 #[derive(Debug)]
@@ -22,13 +28,14 @@ impl SchematicLocation {
         }
     }
 
-    fn is_adjacent(&self, other: &SchematicLocation) -> bool {
-        if other.line < self.line.saturating_sub(1) || other.line > self.line + 1 {
-            return false;
-        }
-        self.actual_span()
-            .into_iter()
-            .any(|i| other.adjacent_span().contains(&i))
+    fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.actual_span().map(move |column| (self.line, column))
+    }
+
+    // Every cell touching this location, its own cells included.
+    fn neighbourhood(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (self.line.saturating_sub(1)..self.line + 2)
+            .flat_map(move |line| self.adjacent_span().map(move |column| (line, column)))
     }
 }
 
@@ -47,8 +54,104 @@ impl SchematicElement {
             SchematicElement::PartNumber(_, location) => location,
         }
     }
-    fn is_adjacent(&self, other: &SchematicElement) -> bool {
-        self.location().is_adjacent(other.location())
+
+    fn part_number(&self) -> Option<u32> {
+        match self {
+            SchematicElement::PartNumber(val, _) => Some(*val),
+            _ => None,
+        }
+    }
+
+    fn symbol(&self) -> Option<char> {
+        match self {
+            SchematicElement::Symbol(c, _) => Some(*c),
+            _ => None,
+        }
+    }
+}
+
+// Which element covers each cell of the whole grid, so finding an element's
+// neighbours is a lookup per surrounding cell rather than a scan of the lines
+// around it.
+#[derive(Debug)]
+struct Schematic {
+    elements: Vec<SchematicElement>,
+    cells: HashMap<(usize, usize), usize>,
+}
+
+impl Schematic {
+    fn new(lines: Vec<Vec<SchematicElement>>) -> Schematic {
+        let elements: Vec<SchematicElement> = lines.into_iter().flatten().collect();
+        let cells = elements
+            .iter()
+            .enumerate()
+            .flat_map(|(i, el)| el.location().cells().map(move |cell| (cell, i)))
+            .collect();
+        Schematic { elements, cells }
+    }
+
+    // Each neighbour once, however many of its cells touch the element.
+    fn neighbours<'a>(
+        &'a self,
+        element: &'a SchematicElement,
+    ) -> impl Iterator<Item = &'a SchematicElement> + 'a {
+        let own: BTreeSet<(usize, usize)> = element.location().cells().collect();
+        element
+            .location()
+            .neighbourhood()
+            .filter(|cell| !own.contains(cell))
+            .filter_map(|cell| self.cells.get(&cell).copied())
+            .collect::<BTreeSet<usize>>()
+            .into_iter()
+            .map(|i| &self.elements[i])
+    }
+
+    fn parts(&self) -> impl Iterator<Item = &SchematicElement> {
+        self.elements.iter().filter(|el| el.part_number().is_some())
+    }
+
+    fn symbols(&self) -> impl Iterator<Item = &SchematicElement> {
+        self.elements.iter().filter(|el| el.symbol().is_some())
+    }
+
+    fn parts_adjacent_to(&self, symbol: &SchematicElement) -> Vec<u32> {
+        self.neighbours(symbol)
+            .filter_map(|el| el.part_number())
+            .collect()
+    }
+
+    fn symbols_adjacent_to(&self, part: &SchematicElement) -> Vec<char> {
+        self.neighbours(part).filter_map(|el| el.symbol()).collect()
+    }
+
+    // Symbols the rule counts as gears, with the part numbers around each.
+    fn gears<'a>(
+        &'a self,
+        rule: &'a GearRule,
+    ) -> impl Iterator<Item = (&'a SchematicElement, Vec<u32>)> + 'a {
+        self.symbols()
+            .filter(|el| el.symbol().is_some_and(|c| rule.symbols.contains(&c)))
+            .map(|el| (el, self.parts_adjacent_to(el)))
+            .filter(|(_, parts)| parts.len() == rule.parts)
+    }
+}
+
+// The puzzle's gear is a `*` next to exactly two part numbers; `gear_symbols`
+// and `gear_parts` change either.
+#[derive(Debug)]
+struct GearRule {
+    symbols: BTreeSet<char>,
+    parts: usize,
+}
+
+impl GearRule {
+    fn from_params() -> GearRule {
+        GearRule {
+            symbols: params::get("gear_symbols", "*".to_string())
+                .chars()
+                .collect(),
+            parts: params::get("gear_parts", 2),
+        }
     }
 }
 
@@ -116,99 +219,115 @@ fn parse_line(line: &str, line_index: usize) -> Vec<SchematicElement> {
     elements
 }
 
-fn load_elements() -> Vec<Vec<SchematicElement>> {
-    let input = include_str!("../inputs/part1.txt").to_string();
-    input
-        .split("\n")
-        .enumerate()
-        .map(|(i, l)| parse_line(l, i))
-        .collect()
+fn parse_schematic(input: &str) -> Schematic {
+    Schematic::new(
+        input
+            .split("\n")
+            .enumerate()
+            .map(|(i, l)| parse_line(l, i))
+            .collect(),
+    )
+}
+
+fn load_schematic() -> Schematic {
+    parse_schematic(&load_input("part1.txt"))
+}
+
+const PART: Style = Style {
+    name: "part",
+    ansi: "32",
+    css: "#2ca02c",
+};
+const ORPHAN: Style = Style {
+    name: "orphan",
+    ansi: "31",
+    css: "#d62728",
+};
+const GEAR: Style = Style {
+    name: "gear",
+    ansi: "1;33",
+    css: "#ffd700",
+};
+const NOT_GEAR: Style = Style {
+    name: "not-gear",
+    ansi: "35",
+    css: "#9467bd",
+};
+
+// `--ansi` or `--html` draws the schematic with every number coloured by
+// whether it's a part, every gear candidate by whether it qualifies, and each
+// gear's ratio after its line.
+fn render_schematic(format: render::Format) -> String {
+    let input = load_input("part1.txt");
+    let schematic = parse_schematic(&input);
+    let rule = GearRule::from_params();
+    let mut canvas = Canvas::new(&input);
+
+    for part in schematic.parts() {
+        let style = match schematic.symbols_adjacent_to(part).is_empty() {
+            true => ORPHAN,
+            false => PART,
+        };
+        for (line, column) in part.location().cells() {
+            canvas.paint(line, column, style);
+        }
+    }
+    for symbol in schematic.symbols() {
+        if symbol.symbol().is_some_and(|c| rule.symbols.contains(&c)) {
+            let location = symbol.location();
+            canvas.paint(location.line, location.column, NOT_GEAR);
+        }
+    }
+    for (gear, ratios) in schematic.gears(&rule) {
+        let location = gear.location();
+        canvas.paint(location.line, location.column, GEAR);
+        canvas.note(
+            location.line,
+            format!(
+                "@{}: {} = {}",
+                location.column,
+                ratios
+                    .iter()
+                    .map(|r| r.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" * "),
+                ratios.iter().product::<u32>()
+            ),
+        );
+    }
+
+    canvas.render(format, &[PART, ORPHAN, GEAR, NOT_GEAR])
 }
 
 fn part1() {
-    let elements = load_elements();
-
-    let line_symbols: Vec<Vec<&SchematicElement>> = elements
-        .iter()
-        .map(|l| {
-            l.iter()
-                .filter(|x| match x {
-                    SchematicElement::Symbol(_, _) => true,
-                    _ => false,
-                })
-                .collect()
-        })
-        .collect();
-
-    let result: u32 = elements
-        .iter()
-        .enumerate()
-        .map(|(i, l)| {
-            l.iter()
-                .filter(|element| match element {
-                    SchematicElement::PartNumber(_, _) => line_symbols
-                        .iter()
-                        .skip(i.saturating_sub(1))
-                        .take(3)
-                        .flatten()
-                        .any(|other| other.is_adjacent(element)),
-                    _ => false,
-                })
-                .map(|el| match el {
-                    SchematicElement::PartNumber(val, _) => val,
-                    _ => panic!("Should never get here"),
-                })
-                .sum::<u32>()
-        })
+    let schematic = profile::parse(load_schematic);
+
+    let result: u32 = schematic
+        .parts()
+        .filter(|part| !schematic.symbols_adjacent_to(part).is_empty())
+        .filter_map(|part| part.part_number())
         .sum();
-    println!("Part 1 Result: {}", result);
+    output::answer(1, result);
 }
 
 fn part2() {
-    let elements = load_elements();
-
-    let gear_ratios: Vec<Vec<&SchematicElement>> = elements
-        .iter()
-        .map(|l| {
-            l.iter()
-                .filter(|x| match x {
-                    SchematicElement::PartNumber(_, _) => true,
-                    _ => false,
-                })
-                .collect()
-        })
-        .collect();
-
-    let result: u32 = elements
-        .iter()
-        .enumerate()
-        .map(|(i, l)| {
-            l.iter()
-                .filter_map(|element| match element {
-                    SchematicElement::Symbol('*', _) => Some(
-                        gear_ratios
-                            .iter()
-                            .skip(i.saturating_sub(1))
-                            .take(3)
-                            .flatten()
-                            .filter(|other| other.is_adjacent(element))
-                            .filter_map(|el| match el {
-                                SchematicElement::PartNumber(val, _) => Some(val),
-                                _ => None,
-                            })
-                            .collect::<Vec<&u32>>(),
-                    ),
-                    _ => None,
-                })
-                .filter(|ratios| ratios.len() == 2)
-                .map(|ratios| ratios.iter().fold(1, |acc, v| acc * **v))
-                .sum::<u32>()
-        })
-        .sum::<u32>();
-    println!("Part 2 Result: {}", result);
+    let schematic = profile::parse(load_schematic);
+    let rule = GearRule::from_params();
+
+    let result: u32 = schematic
+        .gears(&rule)
+        .map(|(_, ratios)| ratios.iter().product::<u32>())
+        .sum();
+    output::answer(2, result);
 }
 
 fn main() {
-    part1();
-    part2();
+    if let Some(format) = render::requested() {
+        print!("{}", render_schematic(format));
+        return;
+    }
+
+    profile::part(1, part1);
+    profile::part(2, part2);
+    output::finish();
 }
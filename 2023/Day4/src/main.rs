@@ -1,13 +1,69 @@
+use common::checked;
+use common::input::read_lines;
+use common::output;
+use common::params;
+use common::profile;
+use serde::Serialize;
+use std::collections::VecDeque;
 use std::convert::TryInto;
 use std::str::FromStr;
 
-#[allow(dead_code)]
+// Card numbers are all below 128 in practice, so a set of them is a single
+// u128 and matching two is one AND. Anything larger falls back to a sorted
+// vector. Either way a number repeated within a set counts once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NumberSet {
+    Bits(u128),
+    Sorted(Vec<u32>),
+}
+
+impl NumberSet {
+    fn new(mut numbers: Vec<u32>) -> NumberSet {
+        match numbers.iter().all(|n| *n < 128) {
+            true => NumberSet::Bits(numbers.iter().fold(0, |bits, n| bits | 1 << n)),
+            false => {
+                numbers.sort_unstable();
+                numbers.dedup();
+                NumberSet::Sorted(numbers)
+            }
+        }
+    }
+
+    fn to_sorted(&self) -> Vec<u32> {
+        match self {
+            NumberSet::Bits(bits) => (0..128).filter(|n| bits & 1 << n != 0).collect(),
+            NumberSet::Sorted(numbers) => numbers.clone(),
+        }
+    }
+
+    fn common(&self, other: &NumberSet) -> usize {
+        match (self, other) {
+            (NumberSet::Bits(a), NumberSet::Bits(b)) => (a & b).count_ones() as usize,
+            _ => {
+                let (a, b) = (self.to_sorted(), other.to_sorted());
+                let (mut i, mut j, mut count) = (0, 0, 0);
+                while i < a.len() && j < b.len() {
+                    match a[i].cmp(&b[j]) {
+                        std::cmp::Ordering::Less => i += 1,
+                        std::cmp::Ordering::Greater => j += 1,
+                        std::cmp::Ordering::Equal => {
+                            count += 1;
+                            i += 1;
+                            j += 1;
+                        }
+                    }
+                }
+                count
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 struct Card {
     name: String,
-    winning_numbers: Vec<u32>,
-    our_numbers: Vec<u32>,
-    copies: u32,
+    winning_numbers: NumberSet,
+    our_numbers: NumberSet,
 }
 
 impl FromStr for Card {
@@ -35,77 +91,235 @@ impl FromStr for Card {
 
         Ok(Card {
             name: name.to_string(),
-            winning_numbers: number_sets[0].clone(),
-            our_numbers: number_sets[1].clone(),
-            copies: 1,
+            winning_numbers: NumberSet::new(number_sets.first().ok_or(())?.clone()),
+            our_numbers: NumberSet::new(number_sets.get(1).ok_or(())?.clone()),
         })
     }
 }
 
 impl Card {
     fn num_matching(&self) -> usize {
-        self.our_numbers
-            .iter()
-            .filter(|v| self.winning_numbers.contains(v))
-            .count()
+        self.our_numbers.common(&self.winning_numbers)
+    }
+
+    fn value(&self, scoring: &Scoring) -> u64 {
+        scoring.score(self.num_matching())
     }
+}
+
+// Points for a card with a given number of matches. The puzzle doubles for
+// every match after the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Scoring {
+    Doubling,
+    Linear,
+    Fibonacci,
+    // Points for one match, two matches and so on; more matches than the
+    // table covers score its last entry.
+    Custom(Vec<u64>),
+}
 
-    fn value(&self) -> u32 {
-        let matching = self.num_matching();
+impl Scoring {
+    fn score(&self, matching: usize) -> u64 {
         if matching == 0 {
             return 0;
         }
+        match self {
+            Scoring::Doubling => checked::pow(2u64, (matching - 1).try_into().unwrap()),
+            Scoring::Linear => matching as u64,
+            Scoring::Fibonacci => {
+                (1..matching)
+                    .fold((1u64, 1u64), |(a, b), _| (b, checked::add(a, b)))
+                    .0
+            }
+            Scoring::Custom(points) => points[(matching - 1).min(points.len() - 1)],
+        }
+    }
+}
 
-        (2u32).pow((matching - 1).try_into().unwrap())
+// `doubling`, `linear`, `fibonacci` or a comma separated points table.
+impl FromStr for Scoring {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "doubling" => Ok(Scoring::Doubling),
+            "linear" => Ok(Scoring::Linear),
+            "fibonacci" => Ok(Scoring::Fibonacci),
+            table => {
+                let points = table
+                    .split(',')
+                    .map(|p| p.trim().parse::<u64>())
+                    .collect::<Result<Vec<u64>, _>>()
+                    .map_err(|e| format!("Couldn't parse points table {:?}: {}", table, e))?;
+                match points.is_empty() {
+                    true => Err("The points table is empty".to_string()),
+                    false => Ok(Scoring::Custom(points)),
+                }
+            }
+        }
     }
 }
 
-fn load_input() -> String {
-    include_str!("../inputs/part1.txt").to_string()
+// Which cards a card's matches win copies of. Each rule is applied to every
+// instance of a card, originals and copies alike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CopyRule {
+    // One copy of each of the next cards, none past the end of the table. The
+    // puzzle's rule.
+    Capped,
+    // As Capped, but copies won past the last card carry on from the first.
+    // Those cards have already been played, so the copies are counted but win
+    // nothing themselves.
+    Wrap,
+    // As Capped, but each card won is won once per match.
+    Weighted,
 }
 
-#[allow(dead_code)]
-fn part1() {
-    let input = load_input();
+impl FromStr for CopyRule {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "capped" => Ok(CopyRule::Capped),
+            "wrap" => Ok(CopyRule::Wrap),
+            "weighted" => Ok(CopyRule::Weighted),
+            other => Err(format!("Unknown copy rule {:?}", other)),
+        }
+    }
+}
+
+// Plays cards one at a time. A card only ever wins copies of the cards just
+// after it, so the copies still to come are kept for as many cards ahead as the
+// most matches seen so far, however long the table is.
+#[derive(Debug)]
+struct CopyStream {
+    rule: CopyRule,
+    pending: VecDeque<u64>,
+    played: usize,
+}
 
-    let cards: Vec<Card> = input
-        .split("\n")
-        .filter_map(|l| l.parse::<Card>().ok())
-        .collect();
+impl CopyStream {
+    fn new(rule: CopyRule) -> CopyStream {
+        CopyStream {
+            rule,
+            pending: VecDeque::new(),
+            played: 0,
+        }
+    }
 
-    let value: u32 = cards.iter().map(|c| c.value()).sum();
+    // How many instances of the card there are, originals included.
+    fn play(&mut self, matching: usize) -> u64 {
+        let copies = checked::add(1, self.pending.pop_front().unwrap_or(0));
+        let won = match self.rule {
+            CopyRule::Weighted => checked::mul(copies, matching as u64),
+            CopyRule::Capped | CopyRule::Wrap => copies,
+        };
+        if self.pending.len() < matching {
+            self.pending.resize(matching, 0);
+        }
+        self.pending
+            .iter_mut()
+            .take(matching)
+            .for_each(|c| *c = checked::add(*c, won));
+        self.played += 1;
+        copies
+    }
+
+    // Copies won past the last card: dropped unless they wrap, in which case
+    // they land on the card they wrap to. Returns each as (card, copies).
+    fn finish(self) -> Vec<(usize, u64)> {
+        match (self.rule, self.played) {
+            (CopyRule::Wrap, played) if played > 0 => self
+                .pending
+                .into_iter()
+                .enumerate()
+                .filter(|(_, c)| *c > 0)
+                .map(|(k, c)| (k % played, c))
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
 
-    println!("Part 1: Value of all cards: {}", value);
+#[derive(Debug, Clone, Serialize)]
+struct CardReport {
+    name: String,
+    matching: usize,
+    points: u64,
+    copies: u64,
 }
 
-fn part2() {
-    let input = load_input();
-
-    let cards: Vec<Card> = input
-        .split("\n")
-        .filter_map(|l| l.parse::<Card>().ok())
-        .collect();
-
-    let copies = cards.iter().enumerate().fold(
-        (0..cards.len()).map(|_| 1).collect::<Vec<usize>>(),
-        |mut acc, (i, card)| {
-            let val: usize = card.num_matching();
-            let card_copies = acc[i];
-            acc.iter_mut()
-                .skip(i + 1)
-                .take(val)
-                .for_each(|c| *c += card_copies);
-
-            acc
-        },
+fn print_table(reports: &[CardReport]) {
+    let width = reports.iter().map(|r| r.name.len()).max().unwrap_or(0);
+    println!(
+        "{:<width$} {:>8} {:>12} {:>12}",
+        "Card", "Matching", "Points", "Copies"
     );
+    for r in reports {
+        println!(
+            "{:<width$} {:>8} {:>12} {:>12}",
+            r.name, r.matching, r.points, r.copies
+        );
+    }
+}
+
+// Cards are read as they're played, so only opening the input counts as
+// parsing and memory doesn't grow with the table.
+fn cards(lines: impl Iterator<Item = String>) -> impl Iterator<Item = Card> {
+    lines.filter_map(|l| l.parse::<Card>().ok())
+}
+
+fn part1() {
+    let lines = profile::parse(|| read_lines("part1.txt"));
+
+    let scoring = params::get("scoring", Scoring::Doubling);
+    let value: u64 = checked::sum(cards(lines).map(|c| c.value(&scoring)));
+
+    output::answer(1, value);
+}
+
+fn part2() {
+    let lines = profile::parse(|| read_lines("part1.txt"));
 
-    let total: usize = copies.iter().sum();
+    let scoring = params::get("scoring", Scoring::Doubling);
+    let mut stream = CopyStream::new(params::get("copies", CopyRule::Capped));
+    // Only kept when they'll be shown, so the answer alone needs no memory
+    // per card.
+    // `table=true` prints every card's matches, points and copies ahead of the
+    // answer.
+    let table = params::get("table", false);
+    let detailed = table || output::json_requested();
+    let mut reports: Vec<CardReport> = vec![];
+    let mut total: u64 = 0;
+    for card in cards(lines) {
+        let matching = card.num_matching();
+        let copies = stream.play(matching);
+        total = checked::add(total, copies);
+        if detailed {
+            reports.push(CardReport {
+                points: card.value(&scoring),
+                name: card.name,
+                matching,
+                copies,
+            });
+        }
+    }
+    for (card, copies) in stream.finish() {
+        total = checked::add(total, copies);
+        if let Some(report) = reports.get_mut(card) {
+            report.copies = checked::add(report.copies, copies);
+        }
+    }
+
+    if table {
+        print_table(&reports);
+    }
+    output::detail("cards", || reports);
 
-    println!("Part 2: Value of all cards: {}", total);
+    output::answer(2, total);
 }
 
 fn main() {
-    part1();
-    part2();
+    profile::part(1, part1);
+    profile::part(2, part2);
+    output::finish();
 }
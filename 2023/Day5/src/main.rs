@@ -1,70 +1,177 @@
+use common::input::load_input;
+use common::output;
+use common::params;
+use common::profile;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Display;
 use std::ops::Range as StdRange;
 use std::str::FromStr;
 
-#[derive(Debug, Clone, Hash)]
-struct Category {
-    name: String,
-    ranges: Vec<Range>,
-    next_category: Option<Box<Category>>,
-}
-
-impl Category {
-    fn from_line_iter<'a, I>(line_iter: &mut I) -> Option<Category>
-    where
-        I: Iterator<Item = &'a str>,
-    {
-        let category_line = line_iter.next();
-
-        if !category_line?.trim().ends_with("map:") {
-            println!("Doesnt end with map: {:?}", category_line);
-            return None;
+fn shift(val: usize, offset: i64) -> usize {
+    (val as i64 + offset) as usize
+}
+
+fn shift_range(range: &StdRange<usize>, offset: i64) -> StdRange<usize> {
+    shift(range.start, offset)..shift(range.end, offset)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct Piece {
+    domain: StdRange<usize>,
+    offset: i64,
+}
+
+// A map as the values it moves and how far. Pieces are sorted and don't
+// overlap, and every value outside them maps to itself, so one almanac map and
+// any chain of them composed take the same form.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+struct Piecewise {
+    pieces: Vec<Piece>,
+}
+
+impl Piecewise {
+    fn from_ranges(ranges: &[Range]) -> Piecewise {
+        Piecewise::from_pieces(
+            ranges
+                .iter()
+                .map(|r| Piece {
+                    domain: r.source(),
+                    offset: r.offset(),
+                })
+                .collect(),
+        )
+    }
+
+    // Sorts, merges neighbours that move by the same amount and drops the
+    // ones that don't move at all.
+    fn from_pieces(mut pieces: Vec<Piece>) -> Piecewise {
+        pieces.retain(|p| !p.domain.is_empty() && p.offset != 0);
+        pieces.sort_by_key(|p| p.domain.start);
+        let mut merged: Vec<Piece> = vec![];
+        for piece in pieces {
+            match merged.last_mut() {
+                Some(last)
+                    if last.domain.end == piece.domain.start && last.offset == piece.offset =>
+                {
+                    last.domain.end = piece.domain.end
+                }
+                _ => merged.push(piece),
+            }
         }
+        Piecewise { pieces: merged }
+    }
+
+    // `input` cut where the pieces start and end, with how far each part moves.
+    // Relies on the pieces being sorted and not overlapping.
+    fn split(&self, input: StdRange<usize>) -> Vec<(StdRange<usize>, i64)> {
+        let mut parts = vec![];
+        let mut start = input.start;
+        for piece in &self.pieces {
+            if piece.domain.start >= input.end {
+                break;
+            }
+            if piece.domain.end <= start {
+                continue;
+            }
+            if start < piece.domain.start {
+                parts.push((start..piece.domain.start, 0));
+                start = piece.domain.start;
+            }
+            let end = piece.domain.end.min(input.end);
+            parts.push((start..end, piece.offset));
+            start = end;
+        }
+        if start < input.end {
+            parts.push((start..input.end, 0));
+        }
+        parts
+    }
+
+    fn apply(&self, val: usize) -> usize {
+        self.split(val..val + 1)
+            .first()
+            .map_or(val, |(_, offset)| shift(val, *offset))
+    }
 
-        let category_name = category_line?.split("-").next().unwrap();
+    // The image of `input`. Parts covered by a piece are shifted with it and
+    // the gaps between pieces pass through as they are.
+    fn map_range(&self, input: StdRange<usize>) -> Vec<StdRange<usize>> {
+        self.split(input)
+            .iter()
+            .map(|(part, offset)| shift_range(part, *offset))
+            .collect()
+    }
 
-        let ranges = line_iter
-            .take_while(|l| !l.trim().is_empty())
-            .filter_map(|l| l.trim().parse::<Range>().ok())
+    // Every value that maps to `val`. Maps needn't be one to one, so there may
+    // be several or none.
+    fn inverse(&self, val: usize) -> Vec<usize> {
+        let mut sources: Vec<usize> = self
+            .pieces
+            .iter()
+            .filter(|p| shift_range(&p.domain, p.offset).contains(&val))
+            .map(|p| shift(val, -p.offset))
             .collect();
+        if !self.pieces.iter().any(|p| p.domain.contains(&val)) {
+            sources.push(val);
+        }
+        sources.sort_unstable();
+        sources
+    }
+
+    // This map followed by `next`.
+    fn then(&self, next: &Piecewise) -> Piecewise {
+        let mut pieces: Vec<Piece> = vec![];
+        for piece in &self.pieces {
+            for (part, offset) in next.split(shift_range(&piece.domain, piece.offset)) {
+                pieces.push(Piece {
+                    domain: shift_range(&part, -piece.offset),
+                    offset: piece.offset + offset,
+                });
+            }
+        }
+        // Where this map leaves values alone only `next` moves them.
+        for piece in &next.pieces {
+            for (part, offset) in self.split(piece.domain.clone()) {
+                if offset == 0 {
+                    pieces.push(Piece {
+                        domain: part,
+                        offset: piece.offset,
+                    });
+                }
+            }
+        }
+        Piecewise::from_pieces(pieces)
+    }
+}
+
+impl Display for Piecewise {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for piece in &self.pieces {
+            let image = shift_range(&piece.domain, piece.offset);
+            writeln!(
+                f,
+                "{}..{} -> {}..{} ({:+})",
+                piece.domain.start, piece.domain.end, image.start, image.end, piece.offset
+            )?;
+        }
+        write!(f, "anything else maps to itself")
+    }
+}
 
-        let next_category = match Category::from_line_iter(line_iter) {
-            Some(cat) => Some(Box::new(cat)),
-            _ => None,
-        };
-
-        Some(Category {
-            name: category_name.to_string(),
-            ranges,
-            next_category,
-        })
-    }
-
-    fn range_for(&self, val: usize) -> Option<Range> {
-        self.ranges.iter().find(|r| r.contains(val)).cloned()
-    }
-
-    fn lookup(&self, val: usize) -> usize {
-        let rebased_val = match self.range_for(val) {
-            Some(range) => range.rebase(val),
-            _ => val,
-        };
-
-        // println!(
-        //     "{} in {} maps to {} in {}",
-        //     val,
-        //     self.name,
-        //     rebased_val,
-        //     match &self.next_category {
-        //         Some(cat) => &cat.name,
-        //         _ => "End",
-        //     }
-        // );
-
-        match &self.next_category {
-            Some(cat) => cat.lookup(rebased_val),
-            _ => rebased_val,
+// Sorted, with overlapping and touching ranges merged and empty ones dropped.
+fn disjoint(mut ranges: Vec<StdRange<usize>>) -> Vec<StdRange<usize>> {
+    ranges.retain(|r| !r.is_empty());
+    ranges.sort_by_key(|r| r.start);
+    let mut merged: Vec<StdRange<usize>> = vec![];
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
         }
     }
+    merged
 }
 
 #[derive(Debug, Clone, Hash)]
@@ -75,12 +182,12 @@ struct Range {
 }
 
 impl Range {
-    fn contains(&self, val: usize) -> bool {
-        self.start <= val && val < self.start + self.width
+    fn source(&self) -> StdRange<usize> {
+        self.start..self.start + self.width
     }
 
-    fn rebase(&self, val: usize) -> usize {
-        self.dest_start + (val - self.start)
+    fn offset(&self) -> i64 {
+        self.dest_start as i64 - self.start as i64
     }
 }
 
@@ -104,84 +211,235 @@ impl FromStr for Range {
     }
 }
 
-#[allow(dead_code)]
+// Every map in the almanac, keyed by the categories it maps between. Nothing
+// assumes they form a single chain, so any category can be looked up from any
+// other it's connected to.
+#[derive(Debug, Clone)]
+struct Almanac {
+    seeds: Vec<usize>,
+    maps: BTreeMap<(String, String), Piecewise>,
+}
+
+impl FromStr for Almanac {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Almanac, String> {
+        let mut blocks = s.split("\n\n");
+
+        let seeds: Vec<usize> = blocks
+            .next()
+            .and_then(|l| l.trim().strip_prefix("seeds: "))
+            .ok_or("Should have started with 'seeds: '")?
+            .split_whitespace()
+            .map(|v| {
+                v.parse::<usize>()
+                    .map_err(|e| format!("Bad seed {:?}: {}", v, e))
+            })
+            .collect::<Result<Vec<usize>, String>>()?;
+
+        let mut maps = BTreeMap::new();
+        for block in blocks.filter(|b| !b.trim().is_empty()) {
+            let mut lines = block.trim().lines();
+            let header = lines.next().unwrap_or_default();
+            let (source, dest) = header
+                .strip_suffix(" map:")
+                .and_then(|names| names.split_once("-to-"))
+                .ok_or(format!(
+                    "Expected `<source>-to-<destination> map:`, got {:?}",
+                    header
+                ))?;
+            let ranges: Vec<Range> = lines
+                .filter_map(|l| l.trim().parse::<Range>().ok())
+                .collect();
+            maps.insert(
+                (source.to_string(), dest.to_string()),
+                Piecewise::from_ranges(&ranges),
+            );
+        }
+
+        Ok(Almanac { seeds, maps })
+    }
+}
+
+impl Almanac {
+    // The categories passed through on the way from `from` to `to`, both
+    // included, or None if no chain of maps joins them.
+    fn path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        let mut previous: BTreeMap<&str, &str> = BTreeMap::new();
+        let mut queue: VecDeque<&str> = VecDeque::from([from]);
+        while let Some(category) = queue.pop_front() {
+            if category == to {
+                let mut path = vec![to.to_string()];
+                let mut current = to;
+                while let Some(prev) = previous.get(current) {
+                    path.push(prev.to_string());
+                    current = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for (source, dest) in self.maps.keys() {
+                if source == category && dest != from && !previous.contains_key(dest.as_str()) {
+                    previous.insert(dest, source);
+                    queue.push_back(dest);
+                }
+            }
+        }
+        None
+    }
+
+    // Every map on the way from `from` to `to` as one.
+    fn compose(&self, from: &str, to: &str) -> Option<Piecewise> {
+        let path = self.path(from, to)?;
+        Some(
+            path.windows(2)
+                .fold(Piecewise::default(), |composed, step| {
+                    composed.then(&self.maps[&(step[0].clone(), step[1].clone())])
+                }),
+        )
+    }
+
+    fn lookup(&self, from: &str, to: &str, val: usize) -> Option<usize> {
+        Some(self.compose(from, to)?.apply(val))
+    }
+
+    // The values of `from` that end up as `val` in `to`.
+    fn inverse_lookup(&self, from: &str, to: &str, val: usize) -> Option<Vec<usize>> {
+        Some(self.compose(from, to)?.inverse(val))
+    }
+
+    fn seed_ranges(&self) -> Vec<StdRange<usize>> {
+        self.seeds
+            .chunks(2)
+            .map(|params| {
+                let start = params[0];
+                let width = params[1];
+
+                StdRange {
+                    start,
+                    end: start + width,
+                }
+            })
+            .collect()
+    }
+}
+
+fn load_almanac() -> Almanac {
+    load_input("part1.txt")
+        .parse()
+        .unwrap_or_else(|e| panic!("Couldn't parse the almanac: {}", e))
+}
+
+fn print_piecewise() {
+    let almanac = load_almanac();
+    let from = params::get("from", "seed".to_string());
+    let to = params::get("to", "location".to_string());
+    match almanac.compose(&from, &to) {
+        Some(composed) => println!("{} to {}\n{}", from, to, composed),
+        None => println!("No maps lead from {} to {}", from, to),
+    }
+}
+
 fn part1() {
-    let s = include_str!("../inputs/part1.txt");
-
-    let mut line_iter = s.split("\n").into_iter();
-
-    let seeds: Vec<usize> = line_iter
-        .next()
-        .expect("Expected at least 1 line...")
-        .strip_prefix("seeds: ")
-        .expect("Should have started with 'seeds: '")
-        .split(" ")
-        .map(|v| v.parse::<usize>().unwrap())
-        .collect();
-
-    println!("Seeds: {:?}", seeds);
-
-    let main_category = match line_iter.next() {
-        Some("") => match Category::from_line_iter(&mut line_iter) {
-            Some(cat) => cat,
-            _ => return,
-        },
-        _ => return,
-    };
-
-    let result: usize = seeds
+    let almanac = profile::parse(load_almanac);
+
+    println!("Seeds: {:?}", almanac.seeds);
+
+    let result: usize = almanac
+        .seeds
         .iter()
-        .map(|s| main_category.lookup(*s))
+        .filter_map(|s| almanac.lookup("seed", "location", *s))
         .min()
         .expect("Should always get a result.");
 
-    println!("Part 1 Result: {:?}", result);
+    output::answer(1, result);
 }
 
-#[allow(dead_code)]
 fn part2() {
-    let s = include_str!("../inputs/part1.txt");
-
-    let mut line_iter = s.split("\n").into_iter();
-
-    let seed_range_params: Vec<usize> = line_iter
-        .next()
-        .expect("Expected at least 1 line...")
-        .strip_prefix("seeds: ")
-        .expect("Should have started with 'seeds: '")
-        .split(" ")
-        .map(|v| v.parse::<usize>().unwrap())
-        .collect();
-
-    let seeds = seed_range_params
-        .chunks(2)
-        .map(|params| {
-            let start = params[0];
-            let width = params[1];
-
-            StdRange {
-                start,
-                end: start + width,
-            }
-        })
-        .flat_map(|r| r.clone());
-    //
-    let main_category = match line_iter.next() {
-        Some("") => match Category::from_line_iter(&mut line_iter) {
-            Some(cat) => cat,
-            _ => return,
-        },
-        _ => return,
-    };
-
-    let result: usize = seeds
-        .map(|s| main_category.lookup(s))
-        .min()
+    let almanac = profile::parse(load_almanac);
+    let seed_ranges = disjoint(almanac.seed_ranges());
+
+    let composed = almanac
+        .compose("seed", "location")
+        .expect("Seeds should lead to locations");
+    // The ranges come back sorted, so the lowest location starts the first.
+    let locations = disjoint(
+        seed_ranges
+            .iter()
+            .flat_map(|r| composed.map_range(r.clone()))
+            .collect(),
+    );
+    let result: usize = locations
+        .first()
+        .map(|r| r.start)
         .expect("Should always get a result.");
 
-    println!("Part 2 Result: {:?}", result);
+    output::detail("seed_to_location", || composed.clone());
+    output::detail("lowest_location_seeds", || {
+        almanac
+            .inverse_lookup("seed", "location", result)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| seed_ranges.iter().any(|r| r.contains(s)))
+            .collect::<Vec<usize>>()
+    });
+
+    output::answer(2, result);
 }
 
 fn main() {
-    part2();
+    // `piecewise=true` prints every map from `from` to `to`, seed to location
+    // unless configured, composed into one.
+    if params::get("piecewise", false) {
+        print_piecewise();
+        return;
+    }
+
+    profile::part(1, part1);
+    profile::part(2, part2);
+    output::finish();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn piecewise(pieces: &[(StdRange<usize>, i64)]) -> Piecewise {
+        Piecewise::from_pieces(
+            pieces
+                .iter()
+                .map(|(domain, offset)| Piece {
+                    domain: domain.clone(),
+                    offset: *offset,
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn map_range_shifts_covered_parts_and_passes_gaps_through() {
+        let map = piecewise(&[(10..20, 5), (30..40, -10)]);
+
+        assert_eq!(map.map_range(5..35), [5..10, 15..25, 20..30, 20..25]);
+        assert_eq!(map.map_range(12..25), [17..25, 20..25]);
+        assert_eq!(map.map_range(35..60), [25..30, 40..60]);
+    }
+
+    #[test]
+    fn then_agrees_with_applying_both_maps() {
+        let first = piecewise(&[(10..20, 5), (30..40, -10)]);
+        let next = piecewise(&[(0..5, 1), (15..18, 100), (22..32, 3)]);
+        let composed = first.then(&next);
+
+        for val in 0..60 {
+            assert_eq!(composed.apply(val), next.apply(first.apply(val)), "{}", val);
+        }
+    }
+
+    #[test]
+    fn then_drops_moves_that_cancel_out() {
+        let first = piecewise(&[(0..10, 5)]);
+        let next = piecewise(&[(5..15, -5)]);
+
+        assert_eq!(first.then(&next), piecewise(&[(10..15, -5)]));
+    }
 }
@@ -1,9 +1,12 @@
+use common::input::load_input;
+use common::output;
+use common::profile;
+
 fn num_ways_to_win(time: u64, record: u64) -> u64 {
     (0..time).filter(|t| (time - t) * t > record).count() as u64
 }
 
-fn main() {
-    let input = include_str!("../inputs/part2.txt");
+fn parse_race(input: &str) -> (u64, u64) {
     let times_line = input.lines().nth(0).unwrap();
     let distance_line = input.lines().nth(1).unwrap();
     let time = times_line
@@ -20,11 +23,23 @@ fn main() {
         .parse::<u64>()
         .unwrap();
 
+    (time, distance)
+}
+
+fn part2() {
+    let input = load_input("part2.txt");
+    let (time, distance) = profile::parse(|| parse_race(&input));
+
     let answer = num_ways_to_win(time, distance);
 
     println!("Times: {:?}", time);
     println!("Distances: {:?}", distance);
-    println!("Answer: {}", answer);
+    output::answer(2, answer);
 
     ()
 }
+
+fn main() {
+    profile::part(2, part2);
+    output::finish();
+}
@@ -1,9 +1,11 @@
+use common::checked;
+use common::input::load_input;
+use common::output;
+use common::parallel;
+use common::profile;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::convert::TryInto;
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
 use std::str::FromStr;
 
 #[repr(u8)]
@@ -97,12 +99,12 @@ impl HandType {
 #[derive(Debug, Eq)]
 struct Hand {
     cards: [Card; 5],
-    bid: u32,
+    bid: u64,
     hand_type: HandType,
 }
 
 impl Hand {
-    fn from(cards: [Card; 5], bid: u32) -> Hand {
+    fn from(cards: [Card; 5], bid: u64) -> Hand {
         let hand_type = HandType::from(&cards);
         Hand {
             cards,
@@ -137,7 +139,7 @@ impl FromStr for Hand {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         s.split_once(' ')
             .map(|(cards, bid)| {
-                let bid: u32 = bid.parse().unwrap();
+                let bid: u64 = bid.parse().unwrap();
                 let cards: [Card; 5] = cards
                     .chars()
                     .map(|card| card.to_string().parse().unwrap())
@@ -151,27 +153,27 @@ impl FromStr for Hand {
     }
 }
 
-fn main() {
-    let input_file = File::open("inputs/part2.txt").unwrap();
-    let reader = BufReader::new(input_file);
-
-    let mut hands = reader
-        .lines()
-        .map(|line| {
-            line.expect("Expected line to be present but received none.")
-                .parse::<Hand>()
-                .unwrap()
-        })
-        .collect::<Vec<Hand>>();
+fn load_hands() -> Vec<Hand> {
+    let input = load_input("part2.txt");
+    let lines = input.lines().collect::<Vec<&str>>();
+    parallel::map(&lines, |line| line.parse::<Hand>().unwrap())
+}
+
+fn part2() {
+    let mut hands = profile::parse(load_hands);
 
     hands.sort();
 
-    let winning_total = hands
-        .iter()
-        .enumerate()
-        .fold(0, |acc, (i, e)| acc + (i as u32 + 1) * e.bid);
+    let winning_total = hands.iter().enumerate().fold(0, |acc, (i, e)| {
+        checked::add(acc, checked::mul(i as u64 + 1, e.bid))
+    });
 
-    println!("Total: {}", winning_total);
+    output::answer(2, winning_total);
 
     ()
 }
+
+fn main() {
+    profile::part(2, part2);
+    output::finish();
+}
@@ -1,3 +1,7 @@
+use common::graph::{self, ExportGraph, Highlight};
+use common::input::load_input;
+use common::output;
+use common::profile;
 use std::cmp::{max, min};
 use std::convert::TryInto;
 use std::{collections::HashMap, str::FromStr};
@@ -7,6 +11,12 @@ struct Identifier {
     key: [u8; 3],
 }
 
+impl Identifier {
+    fn name(&self) -> String {
+        String::from_utf8_lossy(&self.key).to_string()
+    }
+}
+
 impl FromStr for Identifier {
     type Err = ();
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -92,14 +102,14 @@ fn naive_lcm(a: usize, b: usize) -> usize {
     high
 }
 
-fn count_from(
+fn path_from(
     location: &Node,
     lmap: &HashMap<Identifier, Node>,
     sequence: Vec<Direction>,
-) -> usize {
+) -> Vec<Identifier> {
     let mut instructions = Instructions { sequence, idx: 0 };
 
-    let mut count = 0;
+    let mut path = vec![location.identifier];
     let mut loc = location;
     while loc.identifier.key[2] != b'Z' {
         let nexti = instructions
@@ -108,9 +118,19 @@ fn count_from(
         loc = lmap
             .get(&loc.direction(nexti))
             .expect("Should always have a node.");
-        count += 1;
+        path.push(loc.identifier);
     }
 
+    path
+}
+
+fn count_from(
+    location: &Node,
+    lmap: &HashMap<Identifier, Node>,
+    sequence: Vec<Direction>,
+) -> usize {
+    let count = path_from(location, lmap, sequence).len() - 1;
+
     println!(
         "Finished {:?} with {:?} cycles.",
         location.identifier.key, count
@@ -118,10 +138,7 @@ fn count_from(
     count
 }
 
-// Need to refactor to find the num cycles for each and then find the LCM of those.
-fn main() {
-    let input = include_str!("../inputs/part2.txt");
-
+fn load_network(input: &str) -> (Vec<Direction>, HashMap<Identifier, Node>) {
     let sequence: Vec<Direction> = input
         .lines()
         .nth(0)
@@ -157,6 +174,72 @@ fn main() {
         })
         .clone();
 
+    (sequence, locations)
+}
+
+struct Network<'a>(&'a HashMap<Identifier, Node>);
+
+impl ExportGraph for Network<'_> {
+    fn nodes(&self) -> Vec<graph::Node> {
+        self.0
+            .keys()
+            .map(|id| graph::Node {
+                id: id.name(),
+                label: id.name(),
+                kind: match id.key[2] {
+                    b'A' => "start",
+                    b'Z' => "end",
+                    _ => "node",
+                }
+                .to_string(),
+            })
+            .collect()
+    }
+
+    fn edges(&self) -> Vec<graph::Edge> {
+        self.0
+            .values()
+            .flat_map(|n| {
+                [("L", n.left), ("R", n.right)].map(|(label, to)| graph::Edge {
+                    from: n.identifier.name(),
+                    to: to.name(),
+                    label: label.to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+// Highlights the walk from the first start node to whichever end it reaches.
+fn export_graph(format: graph::Format) {
+    let input = load_input("part2.txt");
+    let (sequence, locations) = load_network(&input);
+
+    let start = locations
+        .values()
+        .filter(|n| n.identifier.key[2] == b'A')
+        .min_by_key(|n| n.identifier.key);
+    let highlight = match start {
+        Some(node) => Highlight::path(
+            &path_from(node, &locations, sequence)
+                .iter()
+                .map(|id| id.name())
+                .collect::<Vec<_>>(),
+        ),
+        None => Highlight::default(),
+    };
+
+    print!(
+        "{}",
+        graph::render(&Network(&locations), format, &highlight)
+    );
+}
+
+// Need to refactor to find the num cycles for each and then find the LCM of those.
+fn part2() {
+    let input = load_input("part2.txt");
+    let (sequence, locations) = profile::parse(|| load_network(&input));
+
     let answer = locations
         .iter()
         .filter(|(k, _)| k.key[2] == b'A')
@@ -169,8 +252,17 @@ fn main() {
         .reduce(|acc, v| naive_lcm(acc, v))
         .unwrap();
     //
-    println!("Steps: {:?}", answer);
+    output::answer(2, answer);
 
     ()
 }
 //
+
+fn main() {
+    if let Some(format) = graph::requested() {
+        export_graph(format);
+        return;
+    }
+    profile::part(2, part2);
+    output::finish();
+}
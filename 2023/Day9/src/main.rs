@@ -1,37 +1,41 @@
+use common::checked;
+use common::input::load_input;
+use common::output;
+use common::profile;
 use std::{iter::zip, str::FromStr};
 
 #[derive(Debug)]
 struct Sequence {
-    values: Vec<i32>,
+    values: Vec<i64>,
 }
 
 impl Sequence {
     fn derivative(&self) -> Sequence {
         let vals = zip(self.values.iter(), self.values.iter().skip(1))
-            .map(|(a, b)| b - a)
+            .map(|(a, b)| checked::sub(*b, *a))
             .collect();
 
         Sequence { values: vals }
     }
 
-    fn next_value(&self) -> i32 {
+    fn next_value(&self) -> i64 {
         if self.values.iter().all(|x| *x == 0) {
             return 0;
         }
 
         let derivative_next = self.derivative().next_value();
 
-        self.values.iter().last().unwrap() + derivative_next
+        checked::add(*self.values.iter().last().unwrap(), derivative_next)
     }
 
-    fn prev_value(&self) -> i32 {
+    fn prev_value(&self) -> i64 {
         if self.values.iter().all(|x| *x == 0) {
             return 0;
         }
 
         let derivative_prev = self.derivative().prev_value();
 
-        self.values.iter().nth(0).unwrap() - derivative_prev
+        checked::sub(*self.values.iter().nth(0).unwrap(), derivative_prev)
     }
 }
 
@@ -40,44 +44,37 @@ impl FromStr for Sequence {
     fn from_str(s: &str) -> Result<Sequence, ()> {
         let values = s
             .split_whitespace()
-            .map(|v| v.parse::<i32>().unwrap())
+            .map(|v| v.parse::<i64>().unwrap())
             .collect();
 
         Ok(Sequence { values })
     }
 }
 
-fn part1() {
-    let result: i32 = include_str!("../inputs/part1.txt")
+fn load_sequences(input: &str) -> Vec<Sequence> {
+    input
         .split("\n")
-        .filter_map(|l| {
-            if l.is_empty() {
-                return None;
-            }
+        .filter(|l| !l.is_empty())
+        .map(|l| l.parse::<Sequence>().expect("Expected a seq"))
+        .collect()
+}
 
-            Some(l.parse::<Sequence>().expect("Expected a seq").next_value())
-        })
-        .sum();
+fn part1() {
+    let sequences = profile::parse(|| load_sequences(&load_input("part1.txt")));
+    let result: i64 = checked::sum(sequences.iter().map(|s| s.next_value()));
 
-    println!("Part 1 Result: {:?}", result);
+    output::answer(1, result);
 }
 
 fn part2() {
-    let result: i32 = include_str!("../inputs/part2.txt")
-        .split("\n")
-        .filter_map(|l| {
-            if l.is_empty() {
-                return None;
-            }
-
-            Some(l.parse::<Sequence>().expect("Expected a seq").prev_value())
-        })
-        .sum();
+    let sequences = profile::parse(|| load_sequences(&load_input("part2.txt")));
+    let result: i64 = checked::sum(sequences.iter().map(|s| s.prev_value()));
 
-    println!("Part 2 Result: {:?}", result);
+    output::answer(2, result);
 }
 
 fn main() {
-    part1();
-    part2();
+    profile::part(1, part1);
+    profile::part(2, part2);
+    output::finish();
 }
@@ -1,11 +1,10 @@
+use common::input::load_input;
+use common::output;
+use common::profile;
 use std::collections::HashMap;
 
-fn load_input() -> String {
-    include_str!("../inputs/part1.txt").to_string()
-}
-
 fn part1() {
-    let input = load_input();
+    let input = profile::parse(|| load_input("part1.txt"));
     let vals = input.trim().lines().map(|line| {
         line.split(" ")
             .filter_map(|x| match x.parse::<u32>() {
@@ -27,11 +26,11 @@ fn part1() {
         .map(|v| v)
         .sum::<u32>();
 
-    println!("Part 1: {}", diff);
+    output::answer(1, diff);
 }
 
 fn part2() {
-    let input = load_input();
+    let input = profile::parse(|| load_input("part1.txt"));
     let vals = input.trim().lines().map(|line| {
         line.split(" ")
             .filter_map(|x| match x.parse::<u32>() {
@@ -56,10 +55,11 @@ fn part2() {
         .map(|v| right_counts.get(&v).unwrap_or(&0) * *v)
         .sum();
 
-    println!("Part 2: {}", simval);
+    output::answer(2, simval);
 }
 
 fn main() {
-    part1();
-    part2();
+    profile::part(1, part1);
+    profile::part(2, part2);
+    output::finish();
 }
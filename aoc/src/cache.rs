@@ -0,0 +1,85 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+pub struct CacheKey {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub input_hash: String,
+    pub source_hash: String,
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    input_hash: String,
+    source_hash: String,
+    answer: String,
+}
+
+#[derive(Debug)]
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn open(dir: PathBuf) -> Cache {
+        Cache { dir }
+    }
+
+    fn path_for(&self, key: &CacheKey) -> PathBuf {
+        self.dir
+            .join(key.year.to_string())
+            .join(format!("day{}", key.day))
+            .join(format!("part{}", key.part))
+    }
+
+    fn entries(&self, key: &CacheKey) -> Vec<CacheEntry> {
+        fs::read_to_string(self.path_for(key))
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|l| {
+                let mut fields = l.splitn(3, '\t');
+                Some(CacheEntry {
+                    input_hash: fields.next()?.to_string(),
+                    source_hash: fields.next()?.to_string(),
+                    answer: fields.next()?.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn get(&self, key: &CacheKey) -> Option<String> {
+        self.entries(key)
+            .into_iter()
+            .find(|e| e.input_hash == key.input_hash && e.source_hash == key.source_hash)
+            .map(|e| e.answer)
+    }
+
+    // Answers for the same input produced by an older source are dropped
+    // so the store only ever holds one entry per input.
+    pub fn put(&self, key: &CacheKey, answer: &str) -> io::Result<()> {
+        let path = self.path_for(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut entries: Vec<CacheEntry> = self
+            .entries(key)
+            .into_iter()
+            .filter(|e| e.input_hash != key.input_hash)
+            .collect();
+        entries.push(CacheEntry {
+            input_hash: key.input_hash.clone(),
+            source_hash: key.source_hash.clone(),
+            answer: answer.to_string(),
+        });
+
+        let contents: String = entries
+            .iter()
+            .map(|e| format!("{}\t{}\t{}\n", e.input_hash, e.source_hash, e.answer))
+            .collect();
+        fs::write(path, contents)
+    }
+}
@@ -0,0 +1,162 @@
+use crate::day::RealInput;
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const FILE_NAME: &str = ".aoc.toml";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Color {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl Color {
+    pub fn enabled(self) -> bool {
+        match self {
+            Color::Always => true,
+            Color::Never => false,
+            Color::Auto => io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none(),
+        }
+    }
+}
+
+// Everything is optional; a missing file or key keeps the built-in default and
+// a CLI flag always wins over both.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    year: Option<u32>,
+    cache_dir: Option<PathBuf>,
+    input_dir: Option<PathBuf>,
+    // Where the Advent of Code session token is kept, for tools that download
+    // inputs or puzzle pages. Never read by the runner itself.
+    session_file: Option<PathBuf>,
+    timeout: Option<u64>,
+    memory: Option<u64>,
+    format: Option<Format>,
+    color: Option<Color>,
+    // `[days.<year>.<day>]` tables, passed to the solution as `--param`s.
+    days: BTreeMap<String, BTreeMap<String, BTreeMap<String, toml::Value>>>,
+}
+
+// Relative paths are taken from the repository root, `~/` from $HOME.
+fn resolve(root: &Path, path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => root.join(path),
+    }
+}
+
+impl Config {
+    pub fn load(root: &Path) -> Result<Config, String> {
+        let path = root.join(FILE_NAME);
+        match fs::read_to_string(&path) {
+            Ok(contents) => {
+                toml::from_str(&contents).map_err(|e| format!("Couldn't parse {:?}: {}", path, e))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(format!("Couldn't read {:?}: {}", path, e)),
+        }
+    }
+
+    pub fn year(&self, cli: Option<u32>) -> Option<u32> {
+        cli.or(self.year)
+    }
+
+    pub fn cache_dir(&self, root: &Path) -> PathBuf {
+        resolve(
+            root,
+            self.cache_dir.as_deref().unwrap_or(Path::new(".aoc-cache")),
+        )
+    }
+
+    pub fn input_dir(&self, root: &Path) -> Option<PathBuf> {
+        self.input_dir.as_ref().map(|dir| resolve(root, dir))
+    }
+
+    // Where a day's real input lives when it's kept outside the repository.
+    pub fn input_path(&self, root: &Path, year: u32, day: u32) -> Option<PathBuf> {
+        self.input_dir(root)
+            .map(|dir| dir.join(year.to_string()).join(format!("day{}.txt", day)))
+    }
+
+    pub fn session_file(&self, root: &Path) -> Option<PathBuf> {
+        self.session_file.as_ref().map(|path| resolve(root, path))
+    }
+
+    pub fn timeout(&self, cli: Option<u64>) -> Duration {
+        Duration::from_secs(cli.or(self.timeout).unwrap_or(60))
+    }
+
+    // In bytes, like `Limits::memory`; configured in MiB.
+    pub fn memory(&self, cli: Option<u64>) -> Option<u64> {
+        cli.or(self.memory).map(|mb| mb * 1024 * 1024)
+    }
+
+    pub fn format(&self, cli: Option<Format>) -> Format {
+        cli.or(self.format).unwrap_or_default()
+    }
+
+    pub fn color(&self, cli: Option<Color>) -> bool {
+        cli.or(self.color).unwrap_or_default().enabled()
+    }
+
+    pub fn real_input(
+        &self,
+        root: &Path,
+        year: u32,
+        day: u32,
+        cli: &[(String, String)],
+    ) -> RealInput {
+        RealInput {
+            input: self
+                .input_path(root, year, day)
+                .filter(|path| path.is_file()),
+            params: self.params(year, day, cli),
+        }
+    }
+
+    // Sorted by name so the same settings always hash to the same cache key.
+    fn params(&self, year: u32, day: u32, cli: &[(String, String)]) -> Vec<(String, String)> {
+        let mut params: BTreeMap<String, String> = self
+            .days
+            .get(&year.to_string())
+            .and_then(|days| days.get(&day.to_string()))
+            .into_iter()
+            .flatten()
+            .map(|(name, value)| {
+                let value = match value {
+                    toml::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                (name.clone(), value)
+            })
+            .collect();
+        params.extend(cli.iter().cloned());
+        params.into_iter().collect()
+    }
+}
+
+pub fn parse_param(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .filter(|(name, _)| !name.is_empty())
+        .ok_or(format!("Expected name=value, got {:?}", s))
+}
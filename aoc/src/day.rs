@@ -0,0 +1,379 @@
+use crate::config::Format;
+use crate::limits::{self, Limits, Termination};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct Day {
+    pub year: u32,
+    pub day: u32,
+    pub dir: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct Answer {
+    pub part: u32,
+    pub value: String,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct RunOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub answers: Vec<Answer>,
+    pub termination: Termination,
+    pub peak_memory: Option<u64>,
+    // What the solution recorded with `output::detail`, from a JSON run.
+    pub details: Option<Map<String, Value>>,
+}
+
+// How a day is run against its real input: the file, when it's kept outside
+// `inputs/`, and the parameters from `.aoc.toml` and the command line.
+#[derive(Debug, Clone, Default)]
+pub struct RealInput {
+    pub input: Option<PathBuf>,
+    pub params: Vec<(String, String)>,
+}
+
+impl Day {
+    pub fn locate(root: &Path, year: u32, day: u32) -> Option<Day> {
+        let year_dir = root.join(year.to_string());
+
+        [format!("Day{}", day), format!("day{}", day)]
+            .iter()
+            .map(|name| year_dir.join(name))
+            .find(|dir| dir.join("Cargo.toml").is_file())
+            .map(|dir| Day { year, day, dir })
+    }
+
+    pub fn package_name(&self) -> String {
+        fs::read_to_string(self.dir.join("Cargo.toml"))
+            .ok()
+            .and_then(|manifest| {
+                manifest.lines().find_map(|l| {
+                    l.trim()
+                        .strip_prefix("name")
+                        .map(|rest| rest.trim_start_matches([' ', '=']).trim_matches('"'))
+                        .map(|name| name.to_string())
+                })
+            })
+            .unwrap_or_else(|| format!("day{}", self.day))
+    }
+
+    // The `[features]` a day declares. Days only forward the `common`
+    // features they use, and cargo refuses to build with any other.
+    pub fn features(&self) -> Vec<String> {
+        fs::read_to_string(self.dir.join("Cargo.toml"))
+            .map(|manifest| {
+                manifest
+                    .lines()
+                    .map(|l| l.trim())
+                    .skip_while(|l| *l != "[features]")
+                    .skip(1)
+                    .take_while(|l| !l.starts_with('['))
+                    .filter_map(|l| l.split_once('='))
+                    .map(|(name, _)| name.trim().to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn build(&self, features: &[&str]) -> Result<PathBuf, String> {
+        let mut command = Command::new("cargo");
+        command.args(["build", "--release", "--quiet"]);
+        if !features.is_empty() {
+            command.args(["--features", &features.join(",")]);
+        }
+
+        let output = command
+            .current_dir(&self.dir)
+            .output()
+            .map_err(|e| format!("Failed to start cargo: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "Build failed for {} day {}\n{}",
+                self.year,
+                self.day,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        Ok(self
+            .dir
+            .join("target")
+            .join("release")
+            .join(self.package_name()))
+    }
+
+    pub fn run(
+        &self,
+        limits: &Limits,
+        features: &[&str],
+        input: Option<&Path>,
+        params: &[(String, String)],
+        format: Format,
+    ) -> Result<RunOutput, String> {
+        let binary = self.build(features)?;
+        let mut command = Command::new(&binary);
+        command.current_dir(&self.dir).args(param_args(params));
+        if let Some(input) = input {
+            command.env("AOC_INPUT", input);
+        }
+        if format == Format::Json {
+            command.args(["--format", "json"]);
+        }
+
+        // A JSON run prints a line per part in place of its answer line.
+        let supervised = limits::supervise(command, limits, |line| match format {
+            Format::Text => parse_answer(line).is_some(),
+            Format::Json => parse_part_line(line).is_some_and(|l| l.part.is_some()),
+        })?;
+
+        let mut previous = Duration::ZERO;
+        let mut answers: Vec<Answer> = vec![];
+        let mut details = None;
+        for line in supervised.lines.iter() {
+            match format {
+                Format::Text => {
+                    if let Some((part, value)) = parse_answer(&line.text) {
+                        answers.push(Answer {
+                            part,
+                            value,
+                            elapsed: line.at - previous,
+                        });
+                        previous = line.at;
+                    }
+                }
+                Format::Json => {
+                    if let Some(mut part_line) = parse_part_line(&line.text) {
+                        details
+                            .get_or_insert_with(Map::new)
+                            .append(&mut part_line.details);
+                        answers.extend(Answer::from_part_line(part_line));
+                    }
+                }
+            }
+        }
+
+        Ok(RunOutput {
+            stdout: supervised
+                .lines
+                .iter()
+                .map(|l| format!("{}\n", l.text))
+                .collect(),
+            stderr: supervised.stderr,
+            answers,
+            termination: supervised.termination,
+            peak_memory: supervised.peak_memory,
+            details,
+        })
+    }
+
+    // The day's binary run from its directory on the real input.
+    fn command(&self, binary: &Path, real: &RealInput) -> Command {
+        let mut command = Command::new(binary);
+        command
+            .current_dir(&self.dir)
+            .args(param_args(&real.params));
+        if let Some(input) = &real.input {
+            command.env("AOC_INPUT", input);
+        }
+        command
+    }
+
+    // Runs interactively, so the terminal is handed over instead of supervised.
+    pub fn explore(&self, real: &RealInput) -> Result<(), String> {
+        let binary = self.build(&["explore"])?;
+        let status = self
+            .command(&binary, real)
+            .arg("--explore")
+            .status()
+            .map_err(|e| format!("Failed to start {:?}: {}", binary, e))?;
+
+        match status.success() {
+            true => Ok(()),
+            false => Err(format!("Explorer exited with {}", status)),
+        }
+    }
+
+    // `format` is the flag the solution checks for, `--dot` or `--mermaid`.
+    pub fn graph(&self, format: &str, real: &RealInput) -> Result<String, String> {
+        self.export("Graph export", format, real)
+    }
+
+    // `format` is `--ansi` or `--html`.
+    pub fn render(&self, format: &str, real: &RealInput) -> Result<String, String> {
+        self.export("Render", format, real)
+    }
+
+    fn export(&self, what: &str, format: &str, real: &RealInput) -> Result<String, String> {
+        let binary = self.build(&[])?;
+        let output = self
+            .command(&binary, real)
+            .arg(format)
+            .output()
+            .map_err(|e| format!("Failed to start {:?}: {}", binary, e))?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "{} exited with {}\n{}",
+                what,
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    // Parameters change the answers as much as the input does, so they count
+    // as part of it.
+    pub fn input_hash(&self, real: &RealInput) -> String {
+        let files = match &real.input {
+            Some(path) => {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                hash_files(path.parent().unwrap_or(Path::new("")), &[&name])
+            }
+            // The files solutions read without an override. Examples kept
+            // beside them don't change the real answers.
+            None => hash_files(&self.dir, &["inputs/part1.txt", "inputs/part2.txt"]),
+        };
+        if real.params.is_empty() {
+            return files;
+        }
+
+        let mut hasher = Sha256::new();
+        hasher.update(files.as_bytes());
+        for (name, value) in real.params.iter() {
+            hasher.update(format!("{}={}", name, value).as_bytes());
+            hasher.update([0]);
+        }
+        hex(&hasher.finalize())
+    }
+
+    // Path dependencies such as `common` are built into the binary too, so a
+    // change to them has to miss the cache as well.
+    pub fn source_hash(&self) -> String {
+        let mut entries = vec!["Cargo.toml".to_string(), "src".to_string()];
+        for dependency in self.path_dependencies() {
+            entries.push(format!("{}/Cargo.toml", dependency));
+            entries.push(format!("{}/src", dependency));
+        }
+        hash_files(
+            &self.dir,
+            &entries.iter().map(|e| e.as_str()).collect::<Vec<&str>>(),
+        )
+    }
+
+    // The `path = "..."` of every dependency declared that way, relative to the
+    // day's directory.
+    fn path_dependencies(&self) -> Vec<String> {
+        fs::read_to_string(self.dir.join("Cargo.toml"))
+            .map(|manifest| {
+                manifest
+                    .lines()
+                    .filter_map(|l| {
+                        let (_, rest) = l.split_once("path")?;
+                        let rest = rest.trim_start().strip_prefix('=')?.trim_start();
+                        let rest = rest.strip_prefix('"')?;
+                        rest.split_once('"').map(|(path, _)| path.to_string())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+// A line `common::output` prints in a JSON run: a part once it's done, or the
+// details recorded outside any part.
+#[derive(Debug, Deserialize)]
+struct PartLine {
+    part: Option<u32>,
+    answer: Option<Value>,
+    seconds: Option<f64>,
+    details: Map<String, Value>,
+}
+
+fn parse_part_line(line: &str) -> Option<PartLine> {
+    serde_json::from_str(line).ok()
+}
+
+impl Answer {
+    // The solution times each part itself in a JSON run.
+    fn from_part_line(line: PartLine) -> Option<Answer> {
+        Some(Answer {
+            part: line.part?,
+            value: match line.answer? {
+                Value::String(s) => s,
+                other => other.to_string(),
+            },
+            elapsed: line
+                .seconds
+                .and_then(|s| Duration::try_from_secs_f64(s).ok())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+// Solutions read these through `common::params`.
+fn param_args(params: &[(String, String)]) -> Vec<String> {
+    params
+        .iter()
+        .flat_map(|(name, value)| ["--param".to_string(), format!("{}={}", name, value)])
+        .collect()
+}
+
+pub fn parse_answer(line: &str) -> Option<(u32, String)> {
+    let rest = line.trim().strip_prefix("Part ")?;
+    let part = rest
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse::<u32>()
+        .ok()?;
+    let (_, value) = rest.rsplit_once(": ")?;
+
+    Some((part, value.trim().to_string()))
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return;
+    }
+
+    if let Ok(entries) = fs::read_dir(path) {
+        entries
+            .filter_map(|e| e.ok())
+            .for_each(|e| collect_files(&e.path(), files));
+    }
+}
+
+fn hash_files(base: &Path, entries: &[&str]) -> String {
+    let mut files = vec![];
+    entries
+        .iter()
+        .for_each(|e| collect_files(&base.join(e), &mut files));
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for file in files {
+        let relative = file.strip_prefix(base).unwrap_or(&file);
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(fs::read(&file).unwrap_or_default());
+        hasher.update([0]);
+    }
+
+    hex(&hasher.finalize())
+}
+
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
@@ -0,0 +1,209 @@
+use crate::config::Format;
+use crate::day::{parse_answer, Day};
+use crate::limits::Limits;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone)]
+pub struct Example {
+    pub name: String,
+    pub input: PathBuf,
+    pub expected: Vec<(u32, String)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExampleResult {
+    Pass,
+    Fail(Vec<String>),
+    Error(String),
+}
+
+impl Display for ExampleResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExampleResult::Pass => write!(f, "PASS"),
+            ExampleResult::Fail(mismatches) => write!(f, "FAIL ({})", mismatches.join(", ")),
+            ExampleResult::Error(e) => write!(f, "ERROR ({})", e.lines().next().unwrap_or("")),
+        }
+    }
+}
+
+// Every `inputs/<name>.txt` with a sibling `<name>.answers` file is an
+// example. The answers file uses the same `Part N: answer` lines the
+// solutions print.
+pub fn find(day: &Day) -> Vec<Example> {
+    let inputs = day.dir.join("inputs");
+    let mut examples: Vec<Example> = fs::read_dir(&inputs)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "answers"))
+        .filter_map(|answers| {
+            let input = answers.with_extension("txt");
+            if !input.is_file() {
+                return None;
+            }
+
+            Some(Example {
+                name: answers.file_stem()?.to_string_lossy().to_string(),
+                expected: fs::read_to_string(&answers)
+                    .ok()?
+                    .lines()
+                    .filter_map(parse_answer)
+                    .collect(),
+                input,
+            })
+        })
+        .collect();
+
+    examples.sort_by(|a, b| a.name.cmp(&b.name));
+    examples
+}
+
+// Examples are checked against the solution's own defaults unless `params`
+// says otherwise; `.aoc.toml` settings are tuned for the real input.
+pub fn check(
+    day: &Day,
+    example: &Example,
+    limits: &Limits,
+    params: &[(String, String)],
+) -> ExampleResult {
+    let output = match day.run(limits, &[], Some(&example.input), params, Format::Text) {
+        Ok(output) => output,
+        Err(e) => return ExampleResult::Error(e),
+    };
+
+    let mismatches: Vec<String> = example
+        .expected
+        .iter()
+        .filter_map(
+            |(part, expected)| match output.answers.iter().find(|a| a.part == *part) {
+                Some(a) if a.value == *expected => None,
+                Some(a) => Some(format!(
+                    "Part {}: expected {}, got {}",
+                    part, expected, a.value
+                )),
+                None => Some(format!("Part {}: expected {}, got nothing", part, expected)),
+            },
+        )
+        .collect();
+
+    if mismatches.is_empty() {
+        ExampleResult::Pass
+    } else {
+        ExampleResult::Fail(mismatches)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Extracted {
+    pub input: String,
+    pub expected: Vec<(u32, String)>,
+}
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+fn strip_tags(s: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    decode_entities(&text)
+}
+
+// Returns the contents of every `open ... close` span in order.
+fn spans<'a>(s: &'a str, open: &str, close: &str) -> Vec<&'a str> {
+    let mut found = vec![];
+    let mut rest = s;
+    while let Some(start) = rest.find(open) {
+        let after = &rest[start + open.len()..];
+        match after.find(close) {
+            Some(end) => {
+                found.push(&after[..end]);
+                rest = &after[end + close.len()..];
+            }
+            None => break,
+        }
+    }
+    found
+}
+
+// Each puzzle part is an `<article>`. Its first `<pre><code>` block is taken as
+// the example input and its last `<code><em>` as that example's answer. A part
+// without its own example block reuses the previous part's example.
+pub fn extract(page: &str) -> Vec<Extracted> {
+    let mut extracted: Vec<Extracted> = vec![];
+    for (i, article) in spans(page, "<article", "</article>").iter().enumerate() {
+        let part = i as u32 + 1;
+        let answer = spans(article, "<code><em>", "</em></code>")
+            .last()
+            .map(|a| strip_tags(a).trim().to_string());
+
+        if let Some(block) = spans(article, "<pre><code>", "</code></pre>").first() {
+            let input = strip_tags(block);
+            if !extracted.iter().any(|e| e.input == input) {
+                extracted.push(Extracted {
+                    input,
+                    expected: vec![],
+                });
+            }
+        }
+
+        if let (Some(answer), Some(example)) = (answer, extracted.last_mut()) {
+            example.expected.push((part, answer));
+        }
+    }
+    extracted
+}
+
+fn write_file(path: &Path, contents: &str, force: bool) -> Result<(), String> {
+    match fs::read_to_string(path) {
+        Ok(existing) if existing == contents => return Ok(()),
+        Ok(_) if !force => {
+            return Err(format!(
+                "{:?} already exists with different contents, use --force to replace it",
+                path
+            ))
+        }
+        _ => (),
+    }
+    fs::write(path, contents).map_err(|e| format!("Couldn't write {:?}: {}", path, e))
+}
+
+pub fn save(day: &Day, extracted: &[Extracted], force: bool) -> Result<Vec<PathBuf>, String> {
+    let inputs = day.dir.join("inputs");
+    fs::create_dir_all(&inputs).map_err(|e| format!("Couldn't create {:?}: {}", inputs, e))?;
+
+    let mut written = vec![];
+    for (i, example) in extracted.iter().enumerate() {
+        let input = inputs.join(format!("example{}.txt", i + 1));
+        write_file(&input, &example.input, force)?;
+        written.push(input.clone());
+
+        if !example.expected.is_empty() {
+            let answers = input.with_extension("answers");
+            let lines: String = example
+                .expected
+                .iter()
+                .map(|(part, answer)| format!("Part {}: {}\n", part, answer))
+                .collect();
+            write_file(&answers, &lines, force)?;
+            written.push(answers);
+        }
+    }
+    Ok(written)
+}
@@ -0,0 +1,85 @@
+use crate::cache::CacheKey;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub part: u32,
+    pub elapsed: Duration,
+    pub answer: String,
+    pub input_hash: String,
+    pub source_hash: String,
+}
+
+#[derive(Debug)]
+pub struct History {
+    dir: PathBuf,
+}
+
+impl History {
+    pub fn open(dir: PathBuf) -> History {
+        History { dir }
+    }
+
+    fn day_dir(&self, year: u32, day: u32) -> PathBuf {
+        self.dir.join(year.to_string()).join(format!("day{}", day))
+    }
+
+    pub fn record(&self, key: &CacheKey, elapsed: Duration, answer: &str) -> io::Result<()> {
+        let dir = self.day_dir(key.year, key.day);
+        fs::create_dir_all(&dir)?;
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join("history"))?;
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            timestamp,
+            key.part,
+            elapsed.as_nanos(),
+            key.input_hash,
+            key.source_hash,
+            answer
+        )
+    }
+
+    pub fn samples(&self, year: u32, day: u32) -> Vec<Sample> {
+        fs::read_to_string(self.day_dir(year, day).join("history"))
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|l| {
+                let mut fields = l.splitn(6, '\t').skip(1);
+                Some(Sample {
+                    part: fields.next()?.parse().ok()?,
+                    elapsed: Duration::from_nanos(fields.next()?.parse().ok()?),
+                    input_hash: fields.next()?.to_string(),
+                    source_hash: fields.next()?.to_string(),
+                    answer: fields.next()?.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn save_profile(&self, year: u32, day: u32, lines: &[&str]) -> io::Result<()> {
+        let dir = self.day_dir(year, day);
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join("profile"), lines.join("\n"))
+    }
+
+    pub fn profile(&self, year: u32, day: u32) -> Vec<String> {
+        fs::read_to_string(self.day_dir(year, day).join("profile"))
+            .unwrap_or_default()
+            .lines()
+            .map(|l| l.to_string())
+            .collect()
+    }
+}
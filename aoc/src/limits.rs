@@ -0,0 +1,131 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub timeout: Duration,
+    pub memory: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Termination {
+    Exited(bool),
+    Timeout,
+    OutOfMemory,
+}
+
+#[derive(Debug, Clone)]
+pub struct Line {
+    pub text: String,
+    pub at: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct Supervised {
+    pub lines: Vec<Line>,
+    pub stderr: String,
+    pub termination: Termination,
+    pub peak_memory: Option<u64>,
+}
+
+fn peak_memory(pid: u32) -> Option<u64> {
+    fs::read_to_string(format!("/proc/{}/status", pid))
+        .ok()?
+        .lines()
+        .find_map(|l| l.strip_prefix("VmHWM:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(|kb| kb * 1024)
+}
+
+// The timeout is applied per part: every line accepted by `resets_deadline`
+// (i.e. a part's answer) gives the next part a fresh budget.
+pub fn supervise<F>(
+    mut command: Command,
+    limits: &Limits,
+    resets_deadline: F,
+) -> Result<Supervised, String>
+where
+    F: Fn(&str) -> bool,
+{
+    let start = Instant::now();
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start solution: {}", e))?;
+
+    let stdout = child.stdout.take().expect("Stdout should be piped");
+    let mut stderr = child.stderr.take().expect("Stderr should be piped");
+
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    let stderr_reader = thread::spawn(move || {
+        let mut buf = String::new();
+        let _ = stderr.read_to_string(&mut buf);
+        buf
+    });
+
+    let mut lines = vec![];
+    let mut deadline = start + limits.timeout;
+    let mut peak = None;
+    let mut killed = None;
+
+    loop {
+        match rx.recv_timeout(POLL_INTERVAL) {
+            Ok(text) => {
+                let now = Instant::now();
+                if resets_deadline(&text) {
+                    deadline = now + limits.timeout;
+                }
+                lines.push(Line {
+                    text,
+                    at: now - start,
+                });
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if let Some(mem) = peak_memory(child.id()) {
+            peak = Some(mem.max(peak.unwrap_or(0)));
+        }
+
+        if Instant::now() > deadline {
+            killed = Some(Termination::Timeout);
+        } else if matches!((limits.memory, peak), (Some(budget), Some(mem)) if mem > budget) {
+            killed = Some(Termination::OutOfMemory);
+        }
+
+        if killed.is_some() {
+            let _ = child.kill();
+            break;
+        }
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait on solution: {}", e))?;
+
+    Ok(Supervised {
+        lines,
+        stderr: stderr_reader.join().unwrap_or_default(),
+        termination: killed.unwrap_or(Termination::Exited(status.success())),
+        peak_memory: peak,
+    })
+}
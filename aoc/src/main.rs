@@ -0,0 +1,660 @@
+mod cache;
+mod config;
+mod day;
+mod examples;
+mod history;
+mod limits;
+mod report;
+mod watch;
+
+use cache::{Cache, CacheKey};
+use clap::{Parser, Subcommand};
+use config::{Color, Config, Format};
+use day::{Day, RealInput, RunOutput};
+use history::History;
+use limits::{Limits, Termination};
+use serde_json::{json, Map, Value};
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Parser, Debug)]
+#[command(name = "aoc", about = "Runs Advent of Code solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    Run {
+        #[arg(long)]
+        year: Option<u32>,
+        #[arg(long, help = "Runs every day of the year when omitted")]
+        day: Option<u32>,
+        #[arg(long)]
+        part: Option<u32>,
+        #[arg(long)]
+        no_cache: bool,
+        #[arg(
+            long,
+            help = "Wall-clock seconds allowed per part, 60 unless configured"
+        )]
+        timeout: Option<u64>,
+        #[arg(long, help = "Peak memory allowed in MiB")]
+        memory: Option<u64>,
+        #[arg(
+            long,
+            help = "Reports heap usage of each part's parse and solve phases"
+        )]
+        profile: bool,
+        #[arg(long, help = "Fails on the first arithmetic overflow")]
+        checked: bool,
+        #[arg(long, help = "Spreads independent work across all cores")]
+        parallel: bool,
+        #[arg(
+            long = "param",
+            value_name = "NAME=VALUE",
+            value_parser = config::parse_param,
+            help = "Overrides a solution parameter from .aoc.toml"
+        )]
+        params: Vec<(String, String)>,
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+        #[arg(long, value_enum)]
+        color: Option<Color>,
+    },
+    Test {
+        #[arg(long)]
+        year: Option<u32>,
+        #[arg(long)]
+        day: u32,
+        #[arg(
+            long = "param",
+            value_name = "NAME=VALUE",
+            value_parser = config::parse_param,
+            help = "Overrides a solution parameter for every example"
+        )]
+        params: Vec<(String, String)>,
+    },
+    Report {
+        #[arg(long)]
+        year: Option<u32>,
+        #[arg(long, help = "Defaults to .aoc-cache/report/<year>.html")]
+        out: Option<PathBuf>,
+        #[arg(long, help = "Also rewrites the list of days in <year>/README.md")]
+        readme: bool,
+    },
+    Watch {
+        #[arg(long)]
+        year: Option<u32>,
+        #[arg(long)]
+        day: u32,
+        #[arg(
+            long,
+            help = "Wall-clock seconds allowed per part, 60 unless configured"
+        )]
+        timeout: Option<u64>,
+    },
+    Explore {
+        #[arg(long)]
+        year: Option<u32>,
+        #[arg(long)]
+        day: u32,
+    },
+    Graph {
+        #[arg(long)]
+        year: Option<u32>,
+        #[arg(long)]
+        day: u32,
+        #[arg(long, help = "Exports Mermaid instead of Graphviz DOT")]
+        mermaid: bool,
+        #[arg(long, help = "Writes the graph to a file instead of stdout")]
+        out: Option<PathBuf>,
+    },
+    Render {
+        #[arg(long)]
+        year: Option<u32>,
+        #[arg(long)]
+        day: u32,
+        #[arg(long, help = "Renders HTML instead of ANSI colours")]
+        html: bool,
+        #[arg(long, help = "Writes the render to a file instead of stdout")]
+        out: Option<PathBuf>,
+    },
+    Paths,
+    Examples {
+        #[arg(long)]
+        year: Option<u32>,
+        #[arg(long)]
+        day: u32,
+        #[arg(long, help = "Locally saved puzzle page")]
+        from: PathBuf,
+        #[arg(long, help = "Replaces examples that differ from the page")]
+        force: bool,
+    },
+}
+
+#[derive(Debug, Clone)]
+enum PartStatus {
+    Solved(String, Duration),
+    Cached(String),
+    Timeout,
+    OutOfMemory(u64),
+    Failed,
+    Missing,
+}
+
+impl Display for PartStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PartStatus::Solved(answer, elapsed) => write!(f, "{} ({:.2?})", answer, elapsed),
+            PartStatus::Cached(answer) => write!(f, "{} (cached)", answer),
+            PartStatus::Timeout => write!(f, "TIMEOUT"),
+            PartStatus::OutOfMemory(peak) => write!(f, "OOM ({} MiB peak)", peak / 1024 / 1024),
+            PartStatus::Failed => write!(f, "FAILED"),
+            PartStatus::Missing => write!(f, "NO ANSWER"),
+        }
+    }
+}
+
+impl PartStatus {
+    fn answered(&self) -> bool {
+        matches!(self, PartStatus::Solved(..) | PartStatus::Cached(_))
+    }
+
+    // Green when answered, yellow when a limit cut the run short, red otherwise.
+    fn painted(&self) -> String {
+        let colour = match self {
+            PartStatus::Solved(..) | PartStatus::Cached(_) => 32,
+            PartStatus::Timeout | PartStatus::OutOfMemory(_) => 33,
+            PartStatus::Failed | PartStatus::Missing => 31,
+        };
+        format!("\x1b[{}m{}\x1b[0m", colour, self)
+    }
+
+    fn to_json(&self, day: &Day, part: u32, details: Option<&Map<String, Value>>) -> String {
+        let (status, answer, seconds) = match self {
+            PartStatus::Solved(answer, elapsed) => {
+                ("solved", Some(answer), Some(elapsed.as_secs_f64()))
+            }
+            PartStatus::Cached(answer) => ("cached", Some(answer), None),
+            PartStatus::Timeout => ("timeout", None, None),
+            PartStatus::OutOfMemory(_) => ("oom", None, None),
+            PartStatus::Failed => ("failed", None, None),
+            PartStatus::Missing => ("missing", None, None),
+        };
+        json!({
+            "year": day.year,
+            "day": day.day,
+            "part": part,
+            "status": status,
+            "answer": answer,
+            "seconds": seconds,
+            "details": details,
+        })
+        .to_string()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RunOptions {
+    parts: Vec<u32>,
+    no_cache: bool,
+    limits: Limits,
+    profile: bool,
+    checked: bool,
+    parallel: bool,
+    params: Vec<(String, String)>,
+    format: Format,
+    color: bool,
+}
+
+#[derive(Debug, Clone)]
+struct DayResults {
+    statuses: Vec<(u32, PartStatus)>,
+    details: Option<Map<String, Value>>,
+}
+
+fn repo_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("Runner should live inside the repository")
+        .to_path_buf()
+}
+
+fn latest_year(root: &Path) -> u32 {
+    fs::read_dir(root)
+        .expect("Couldn't read repository root")
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.file_name().to_str()?.parse::<u32>().ok())
+        .max()
+        .expect("No year directories found")
+}
+
+fn part_statuses(output: &RunOutput, parts: &[u32]) -> Vec<(u32, PartStatus)> {
+    let unanswered = match output.termination {
+        Termination::Timeout => PartStatus::Timeout,
+        Termination::OutOfMemory => PartStatus::OutOfMemory(output.peak_memory.unwrap_or(0)),
+        Termination::Exited(false) => PartStatus::Failed,
+        Termination::Exited(true) => PartStatus::Missing,
+    };
+
+    parts
+        .iter()
+        .map(|p| {
+            let status = match output.answers.iter().find(|a| a.part == *p) {
+                Some(a) => PartStatus::Solved(a.value.clone(), a.elapsed),
+                _ => unanswered.clone(),
+            };
+            (*p, status)
+        })
+        .collect()
+}
+
+fn run_day(
+    day: &Day,
+    real: &RealInput,
+    cache: &Cache,
+    history: &History,
+    options: &RunOptions,
+) -> Result<DayResults, String> {
+    let input_hash = day.input_hash(real);
+    let source_hash = day.source_hash();
+    let keys: Vec<CacheKey> = options
+        .parts
+        .iter()
+        .map(|p| CacheKey {
+            year: day.year,
+            day: day.day,
+            part: *p,
+            input_hash: input_hash.clone(),
+            source_hash: source_hash.clone(),
+        })
+        .collect();
+
+    // Cached answers have no details to report, so JSON always runs the day.
+    if !options.no_cache
+        && !options.profile
+        && !options.checked
+        && !options.parallel
+        && options.format == Format::Text
+    {
+        let cached: Vec<(u32, PartStatus)> = keys
+            .iter()
+            .filter_map(|k| cache.get(k).map(|a| (k.part, PartStatus::Cached(a))))
+            .collect();
+
+        if cached.len() == keys.len() {
+            return Ok(DayResults {
+                statuses: cached,
+                details: None,
+            });
+        }
+    }
+
+    let mut features = vec![];
+    if options.profile {
+        features.push("profile");
+    }
+    if options.checked {
+        features.push("checked");
+    }
+    if options.parallel {
+        features.push("parallel");
+    }
+    let declared = day.features();
+    let (features, missing): (Vec<&str>, Vec<&str>) = features
+        .into_iter()
+        .partition(|f| declared.iter().any(|d| d == f));
+    if !missing.is_empty() {
+        eprintln!(
+            "{} day {} has no {} feature, running without it",
+            day.year,
+            day.day,
+            missing.join(" or ")
+        );
+    }
+    let output = day.run(
+        &options.limits,
+        &features,
+        real.input.as_deref(),
+        &real.params,
+        options.format,
+    )?;
+
+    for answer in output.answers.iter() {
+        let key = CacheKey {
+            part: answer.part,
+            ..keys[0].clone()
+        };
+        if let Err(e) = cache.put(&key, &answer.value) {
+            eprintln!("Failed to cache part {}: {}", answer.part, e);
+        }
+
+        // Timings under the counting allocator, checked arithmetic or the
+        // thread pool aren't comparable with normal runs.
+        if !options.profile && !options.checked && !options.parallel {
+            if let Err(e) = history.record(&key, answer.elapsed, &answer.value) {
+                eprintln!("Failed to record part {}: {}", answer.part, e);
+            }
+        }
+    }
+
+    // Stdout is kept for the results alone when it's being parsed as JSON.
+    let show = |text: &str| match options.format {
+        Format::Text => print!("{}", text),
+        Format::Json => eprint!("{}", text),
+    };
+
+    if output.answers.is_empty() && output.termination == Termination::Exited(true) {
+        show(&output.stdout);
+    }
+
+    if output.termination == Termination::Exited(false) {
+        eprint!("{}", output.stderr);
+    }
+
+    if options.profile {
+        let profile: Vec<&str> = output
+            .stderr
+            .lines()
+            .filter(|l| l.starts_with("Profile "))
+            .collect();
+        profile
+            .iter()
+            .for_each(|l| show(&format!("{} Day {} {}\n", day.year, day.day, l)));
+        if let Err(e) = history.save_profile(day.year, day.day, &profile) {
+            eprintln!("Failed to save profile: {}", e);
+        }
+    }
+
+    Ok(DayResults {
+        statuses: part_statuses(&output, &options.parts),
+        details: output.details,
+    })
+}
+
+fn run(
+    root: &Path,
+    config: &Config,
+    year: u32,
+    day: Option<u32>,
+    options: &RunOptions,
+) -> Result<(), String> {
+    let cache = Cache::open(config.cache_dir(root));
+    let history = History::open(config.cache_dir(root));
+    let days: Vec<Day> = match day {
+        Some(d) => vec![Day::locate(root, year, d)
+            .ok_or(format!("No solution found for {} day {}", year, d))?],
+        None => (1..=25)
+            .filter_map(|d| Day::locate(root, year, d))
+            .collect(),
+    };
+
+    let mut summary = vec![];
+    for day in days.iter() {
+        let real = config.real_input(root, day.year, day.day, &options.params);
+        let results = match run_day(day, &real, &cache, &history, options) {
+            Ok(results) => results,
+            Err(e) => {
+                eprintln!("{}", e);
+                DayResults {
+                    statuses: options
+                        .parts
+                        .iter()
+                        .map(|p| (*p, PartStatus::Failed))
+                        .collect(),
+                    details: None,
+                }
+            }
+        };
+
+        for (part, status) in results.statuses {
+            if options.format == Format::Json {
+                println!("{}", status.to_json(day, part, results.details.as_ref()));
+                continue;
+            }
+
+            let line = format!(
+                "{} Day {} Part {}: {}",
+                day.year,
+                day.day,
+                part,
+                match options.color {
+                    true => status.painted(),
+                    false => status.to_string(),
+                }
+            );
+            println!("{}", line);
+            summary.push((line, status));
+        }
+    }
+
+    if days.len() > 1 && options.format == Format::Text {
+        println!("\nSummary");
+        summary
+            .iter()
+            .filter(|(_, status)| !status.answered())
+            .for_each(|(line, _)| println!("{}", line));
+        println!(
+            "{}/{} parts answered",
+            summary
+                .iter()
+                .filter(|(_, status)| status.answered())
+                .count(),
+            summary.len()
+        );
+    }
+
+    Ok(())
+}
+
+fn test(
+    root: &Path,
+    config: &Config,
+    year: u32,
+    day: u32,
+    params: &[(String, String)],
+) -> Result<(), String> {
+    let day = Day::locate(root, year, day)
+        .ok_or(format!("No solution found for {} day {}", year, day))?;
+    let limits = Limits {
+        timeout: config.timeout(None),
+        memory: config.memory(None),
+    };
+
+    let examples = examples::find(&day);
+    if examples.is_empty() {
+        return Err(format!("No examples found in {:?}", day.dir.join("inputs")));
+    }
+
+    let mut failed = 0;
+    for example in examples.iter() {
+        let result = examples::check(&day, example, &limits, params);
+        if !matches!(result, examples::ExampleResult::Pass) {
+            failed += 1;
+        }
+        println!("{} Day {} {}: {}", day.year, day.day, example.name, result);
+    }
+
+    if failed > 0 {
+        return Err(format!("{}/{} examples failed", failed, examples.len()));
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let root = repo_root();
+    let config = Config::load(&root).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    let year_or_default =
+        |year: Option<u32>| config.year(year).unwrap_or_else(|| latest_year(&root));
+
+    let result = match cli.command {
+        Commands::Run {
+            year,
+            day,
+            part,
+            no_cache,
+            timeout,
+            memory,
+            profile,
+            checked,
+            parallel,
+            params,
+            format,
+            color,
+        } => {
+            let options = RunOptions {
+                parts: match part {
+                    Some(p) => vec![p],
+                    None => vec![1, 2],
+                },
+                no_cache,
+                limits: Limits {
+                    timeout: config.timeout(timeout),
+                    memory: config.memory(memory),
+                },
+                profile,
+                checked,
+                parallel,
+                params,
+                format: config.format(format),
+                color: config.color(color),
+            };
+            run(&root, &config, year_or_default(year), day, &options)
+        }
+        Commands::Test { year, day, params } => {
+            test(&root, &config, year_or_default(year), day, &params)
+        }
+        Commands::Report { year, out, readme } => {
+            let year = year_or_default(year);
+            let out = out.unwrap_or_else(|| {
+                config
+                    .cache_dir(&root)
+                    .join("report")
+                    .join(format!("{}.html", year))
+            });
+            report::generate(&root, &config, year, &out)
+                .map(|_| println!("Wrote {}", out.display()))
+                .and_then(|_| match readme {
+                    true => report::update_readme(&root, year),
+                    false => Ok(()),
+                })
+        }
+        Commands::Watch { year, day, timeout } => {
+            let year = year_or_default(year);
+            Day::locate(&root, year, day)
+                .ok_or(format!("No solution found for {} day {}", year, day))
+                .and_then(|day| {
+                    watch::watch(
+                        &day,
+                        &Limits {
+                            timeout: config.timeout(timeout),
+                            memory: config.memory(None),
+                        },
+                        &config.real_input(&root, year, day.day, &[]),
+                    )
+                })
+        }
+        Commands::Explore { year, day } => {
+            let year = year_or_default(year);
+            Day::locate(&root, year, day)
+                .ok_or(format!("No solution found for {} day {}", year, day))
+                .and_then(|day| day.explore(&config.real_input(&root, year, day.day, &[])))
+        }
+        Commands::Graph {
+            year,
+            day,
+            mermaid,
+            out,
+        } => {
+            let year = year_or_default(year);
+            let format = match mermaid {
+                true => "--mermaid",
+                false => "--dot",
+            };
+            Day::locate(&root, year, day)
+                .ok_or(format!("No solution found for {} day {}", year, day))
+                .and_then(|day| day.graph(format, &config.real_input(&root, year, day.day, &[])))
+                .and_then(|graph| match &out {
+                    Some(path) => fs::write(path, graph)
+                        .map(|_| println!("Wrote {}", path.display()))
+                        .map_err(|e| format!("Couldn't write {:?}: {}", path, e)),
+                    None => {
+                        print!("{}", graph);
+                        Ok(())
+                    }
+                })
+        }
+        Commands::Render {
+            year,
+            day,
+            html,
+            out,
+        } => {
+            let year = year_or_default(year);
+            let format = match html {
+                true => "--html",
+                false => "--ansi",
+            };
+            Day::locate(&root, year, day)
+                .ok_or(format!("No solution found for {} day {}", year, day))
+                .and_then(|day| day.render(format, &config.real_input(&root, year, day.day, &[])))
+                .and_then(|render| match &out {
+                    Some(path) => fs::write(path, render)
+                        .map(|_| println!("Wrote {}", path.display()))
+                        .map_err(|e| format!("Couldn't write {:?}: {}", path, e)),
+                    None => {
+                        print!("{}", render);
+                        Ok(())
+                    }
+                })
+        }
+        Commands::Paths => {
+            let show = |name: &str, path: Option<PathBuf>| match path {
+                Some(path) => println!("{} = {}", name, path.display()),
+                None => println!("{} is not set", name),
+            };
+            show("cache_dir", Some(config.cache_dir(&root)));
+            show("input_dir", config.input_dir(&root));
+            show("session_file", config.session_file(&root));
+            Ok(())
+        }
+        Commands::Examples {
+            year,
+            day,
+            from,
+            force,
+        } => {
+            let year = year_or_default(year);
+            Day::locate(&root, year, day)
+                .ok_or(format!("No solution found for {} day {}", year, day))
+                .and_then(|day| {
+                    let page = fs::read_to_string(&from)
+                        .map_err(|e| format!("Couldn't read {:?}: {}", from, e))?;
+                    let extracted = examples::extract(&page);
+                    if extracted.is_empty() {
+                        return Err(format!("No examples found in {:?}", from));
+                    }
+                    examples::save(&day, &extracted, force)
+                })
+                .map(|written| {
+                    written
+                        .iter()
+                        .for_each(|path| println!("Wrote {}", path.display()))
+                })
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,275 @@
+use crate::cache::{Cache, CacheKey};
+use crate::config::Config;
+use crate::day::{Day, RealInput};
+use crate::history::{History, Sample};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+const SPARKLINE_SAMPLES: usize = 20;
+
+struct PartReport {
+    answer: Option<String>,
+    // The answer came from a run on another input or older source.
+    stale: bool,
+    samples: Vec<Duration>,
+    profile: Vec<String>,
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn relative_to(from_dir: &Path, to: &Path) -> PathBuf {
+    let from: Vec<Component> = from_dir.components().collect();
+    let target: Vec<Component> = to.components().collect();
+    let common = from
+        .iter()
+        .zip(target.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut path = PathBuf::new();
+    from.iter().skip(common).for_each(|_| path.push(".."));
+    target.iter().skip(common).for_each(|c| path.push(c));
+    path
+}
+
+fn sparkline(samples: &[Duration]) -> String {
+    if samples.len() < 2 {
+        return String::new();
+    }
+
+    let values: Vec<f64> = samples.iter().map(|d| d.as_secs_f64()).collect();
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let range = if max > min { max - min } else { 1.0 };
+    let step = 100.0 / (values.len() - 1) as f64;
+
+    let points: Vec<String> = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            format!(
+                "{:.1},{:.1}",
+                i as f64 * step,
+                19.0 - (v - min) / range * 18.0
+            )
+        })
+        .collect();
+
+    format!(
+        "<svg width=\"100\" height=\"20\"><polyline fill=\"none\" stroke=\"#4a90d9\" points=\"{}\"/></svg>",
+        points.join(" ")
+    )
+}
+
+fn part_report(
+    day: &Day,
+    real: &RealInput,
+    part: u32,
+    cache: &Cache,
+    samples: &[Sample],
+    profile: &[String],
+) -> PartReport {
+    let key = CacheKey {
+        year: day.year,
+        day: day.day,
+        part,
+        input_hash: day.input_hash(real),
+        source_hash: day.source_hash(),
+    };
+    let samples: Vec<&Sample> = samples.iter().filter(|s| s.part == part).collect();
+    let current = cache.get(&key).or_else(|| {
+        samples
+            .iter()
+            .rev()
+            .find(|s| s.input_hash == key.input_hash && s.source_hash == key.source_hash)
+            .map(|s| s.answer.clone())
+    });
+    let stale = current.is_none() && !samples.is_empty();
+
+    PartReport {
+        answer: current.or_else(|| samples.last().map(|s| s.answer.clone())),
+        stale,
+        samples: samples
+            .iter()
+            .rev()
+            .take(SPARKLINE_SAMPLES)
+            .rev()
+            .map(|s| s.elapsed)
+            .collect(),
+        profile: profile
+            .iter()
+            .filter_map(|l| l.strip_prefix(&format!("Profile Part {} ", part)))
+            .map(|l| l.to_string())
+            .collect(),
+    }
+}
+
+fn part_cells(report: &PartReport) -> String {
+    let (star, answer_class) = match (&report.answer, report.stale) {
+        (Some(_), false) => ("<td class=\"star\">&#9733;</td>", ""),
+        (Some(_), true) => (
+            "<td class=\"stale\" title=\"Not run on the current input and source\">&#9734;</td>",
+            " class=\"stale\"",
+        ),
+        (None, _) => ("<td class=\"nostar\">&#9734;</td>", ""),
+    };
+
+    format!(
+        "{}<td{}>{}</td><td>{}</td><td>{}</td><td class=\"profile\">{}</td>",
+        star,
+        answer_class,
+        escape(report.answer.as_deref().unwrap_or("")),
+        report
+            .samples
+            .last()
+            .map(|d| format!("{:.2?}", d))
+            .unwrap_or_default(),
+        sparkline(&report.samples),
+        report
+            .profile
+            .iter()
+            .map(|l| escape(l))
+            .collect::<Vec<_>>()
+            .join("<br>")
+    )
+}
+
+pub fn generate(root: &Path, config: &Config, year: u32, out: &Path) -> Result<(), String> {
+    let cache = Cache::open(config.cache_dir(root));
+    let history = History::open(config.cache_dir(root));
+    let out_dir = out.parent().unwrap_or(Path::new("."));
+
+    let mut rows = vec![];
+    let mut stars = 0;
+    for day in (1..=25).filter_map(|d| Day::locate(root, year, d)) {
+        let samples = history.samples(year, day.day);
+        let profile = history.profile(year, day.day);
+        let real = config.real_input(root, year, day.day, &[]);
+        let parts: Vec<PartReport> = [1, 2]
+            .iter()
+            .map(|p| part_report(&day, &real, *p, &cache, &samples, &profile))
+            .collect();
+        stars += parts
+            .iter()
+            .filter(|p| p.answer.is_some() && !p.stale)
+            .count();
+
+        let source = relative_to(out_dir, &day.dir.join("src").join("main.rs"));
+        rows.push(format!(
+            "<tr><td><a href=\"{}\">Day {}</a></td>{}</tr>",
+            escape(&source.to_string_lossy()),
+            day.day,
+            parts.iter().map(part_cells).collect::<String>()
+        ));
+    }
+
+    let html = format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Advent of Code {year}</title>
+<style>
+body {{ font-family: sans-serif; background: #0f0f23; color: #cccccc; }}
+table {{ border-collapse: collapse; }}
+th, td {{ padding: 4px 8px; border-bottom: 1px solid #333340; text-align: left; vertical-align: top; }}
+a {{ color: #009900; }}
+.star {{ color: #ffff66; }}
+.nostar {{ color: #333340; }}
+.stale {{ color: #999966; font-style: italic; }}
+.profile {{ font-size: 0.8em; color: #999999; }}
+</style>
+</head>
+<body>
+<h1>Advent of Code {year}</h1>
+<p>{stars} stars</p>
+<table>
+<tr><th>Day</th><th colspan="5">Part 1</th><th colspan="5">Part 2</th></tr>
+{rows}
+</table>
+</body>
+</html>
+"#,
+        year = year,
+        stars = stars,
+        rows = rows.join("\n")
+    );
+
+    if let Some(parent) = out.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Couldn't create {:?}: {}", parent, e))?;
+    }
+    fs::write(out, html).map_err(|e| format!("Couldn't write {:?}: {}", out, e))
+}
+
+// The day a `[Day N]...` line is about.
+fn day_line(line: &str) -> Option<u32> {
+    let (day, _) = line.strip_prefix("[Day ")?.split_once(']')?;
+    day.parse().ok()
+}
+
+// A line exactly as `update_readme` writes it, `[Day N](<dir>/)`.
+fn is_day_link(line: &str) -> bool {
+    line.strip_prefix("[Day ")
+        .and_then(|rest| rest.split_once("]("))
+        .is_some_and(|(day, target)| {
+            day.parse::<u32>().is_ok()
+                && target
+                    .strip_suffix("/)")
+                    .is_some_and(|dir| !dir.is_empty() && !dir.contains([' ', '(', ')']))
+        })
+}
+
+// Replaces the list of day links in `<year>/README.md`. A day's line that was
+// edited by hand, such as a link with notes after it, is kept in its place.
+pub fn update_readme(root: &Path, year: u32) -> Result<(), String> {
+    let path = root.join(year.to_string()).join("README.md");
+    let readme = fs::read_to_string(&path).unwrap_or(format!(
+        "# Advent of Code {}\n\nThese are my advent of code solutions for {}.\n",
+        year, year
+    ));
+
+    let mut days: BTreeMap<u32, String> = (1..=25)
+        .filter_map(|d| Day::locate(root, year, d))
+        .map(|day| {
+            let name = day
+                .dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            (day.day, format!("[Day {}]({}/)", day.day, name))
+        })
+        .collect();
+    for line in readme.lines().filter(|l| !is_day_link(l)) {
+        if let Some(day) = day_line(line) {
+            days.insert(day, line.to_string());
+        }
+    }
+
+    let mut lines: Vec<String> = vec![];
+    let mut inserted = false;
+    for line in readme.lines() {
+        if day_line(line).is_some() {
+            if !inserted {
+                lines.extend(days.values().cloned());
+                inserted = true;
+            }
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+
+    if !inserted {
+        lines.push(String::new());
+        lines.extend(days.into_values());
+    }
+
+    fs::write(&path, lines.join("\n") + "\n")
+        .map_err(|e| format!("Couldn't write {:?}: {}", path, e))
+}
@@ -0,0 +1,108 @@
+use crate::config::Format;
+use crate::day::{Day, RealInput};
+use crate::examples;
+use crate::limits::Limits;
+use crate::part_statuses;
+use notify::{Event, RecursiveMode, Watcher};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+fn render(day: &Day, limits: &Limits, real: &RealInput) -> Result<String, String> {
+    let output = day.run(
+        limits,
+        &[],
+        real.input.as_deref(),
+        &real.params,
+        Format::Text,
+    )?;
+
+    let mut lines: Vec<String> = part_statuses(&output, &[1, 2])
+        .iter()
+        .map(|(part, status)| format!("Part {}: {}", part, status))
+        .collect();
+
+    let examples = examples::find(day);
+    if examples.is_empty() {
+        lines.push("No examples".to_string());
+    }
+    for example in examples.iter() {
+        let result = examples::check(day, example, limits, &[]);
+        lines.push(format!("{}: {}", example.name, result));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+fn wait_for_change(
+    rx: &Receiver<notify::Result<Event>>,
+    relevant: impl Fn(&Path) -> bool,
+) -> Result<(), String> {
+    loop {
+        let event = rx
+            .recv()
+            .map_err(|_| "File watcher stopped".to_string())?
+            .map_err(|e| format!("File watcher failed: {}", e))?;
+
+        if (event.kind.is_modify() || event.kind.is_create() || event.kind.is_remove())
+            && event.paths.iter().any(|p| relevant(p))
+        {
+            break;
+        }
+    }
+
+    // Editors tend to write a file several times per save.
+    while rx.recv_timeout(DEBOUNCE).is_ok() {}
+    Ok(())
+}
+
+pub fn watch(day: &Day, limits: &Limits, real: &RealInput) -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).map_err(|e| format!("Couldn't start watcher: {}", e))?;
+
+    for path in ["src", "inputs", "Cargo.toml"] {
+        let path = day.dir.join(path);
+        if path.exists() {
+            watcher
+                .watch(&path, RecursiveMode::Recursive)
+                .map_err(|e| format!("Couldn't watch {:?}: {}", path, e))?;
+        }
+    }
+    // Editors often save by replacing the file, which ends a watch on the file
+    // itself, so its directory is watched and everything else there ignored.
+    if let Some(dir) = real.input.as_ref().and_then(|input| input.parent()) {
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Couldn't watch {:?}: {}", dir, e))?;
+    }
+    let relevant = |path: &Path| {
+        path.starts_with(&day.dir) || real.input.as_deref().is_some_and(|input| path == input)
+    };
+
+    let mut last_result: Option<String> = None;
+    let mut runs = 0;
+    loop {
+        runs += 1;
+        let result = render(day, limits, real);
+
+        print!("\x1b[2J\x1b[H");
+        println!("Watching {} Day {} (run {})\n", day.year, day.day, runs);
+        match result {
+            Ok(result) => {
+                println!("{}", result);
+                last_result = Some(result);
+            }
+            Err(e) => {
+                if let Some(last) = last_result.as_ref() {
+                    println!("{}\n\nShowing the last successful run.", last);
+                }
+                println!("\n{}", e);
+            }
+        }
+
+        wait_for_change(&rx, relevant)?;
+    }
+}
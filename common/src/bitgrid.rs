@@ -0,0 +1,404 @@
+use std::fmt::Display;
+
+const WORD: usize = 64;
+
+// A grid of booleans packed 64 to a word, each row starting on a fresh word.
+// Bits past the last column are always zero so whole words can be compared,
+// XORed and counted without masking.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    rows: usize,
+    columns: usize,
+    stride: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(rows: usize, columns: usize) -> BitGrid {
+        let stride = columns.div_ceil(WORD);
+        BitGrid {
+            rows,
+            columns,
+            stride,
+            words: vec![0; rows * stride],
+        }
+    }
+
+    pub fn parse(s: &str, set: impl Fn(char) -> bool) -> BitGrid {
+        let lines: Vec<&str> = s.lines().filter(|l| !l.is_empty()).collect();
+        let columns = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let mut grid = BitGrid::new(lines.len(), columns);
+        for (row, line) in lines.iter().enumerate() {
+            for (column, c) in line.chars().enumerate() {
+                if set(c) {
+                    grid.set(row, column, true);
+                }
+            }
+        }
+        grid
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn words_per_row(&self) -> usize {
+        self.stride
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> bool {
+        assert!(row < self.rows && column < self.columns, "Out of bounds");
+        self.words[row * self.stride + column / WORD] >> (column % WORD) & 1 == 1
+    }
+
+    pub fn set(&mut self, row: usize, column: usize, value: bool) {
+        assert!(row < self.rows && column < self.columns, "Out of bounds");
+        let word = &mut self.words[row * self.stride + column / WORD];
+        let bit = 1 << (column % WORD);
+        match value {
+            true => *word |= bit,
+            false => *word &= !bit,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    // Column `64 * index` is the lowest bit of word `index`.
+    pub fn row_words(&self, row: usize) -> &[u64] {
+        &self.words[row * self.stride..(row + 1) * self.stride]
+    }
+
+    pub fn word(&self, row: usize, index: usize) -> u64 {
+        self.words[row * self.stride + index]
+    }
+
+    pub fn set_word(&mut self, row: usize, index: usize, value: u64) {
+        self.words[row * self.stride + index] = value & self.mask(index);
+    }
+
+    fn mask(&self, index: usize) -> u64 {
+        match self.columns - index * WORD {
+            n if n >= WORD => u64::MAX,
+            n => (1 << n) - 1,
+        }
+    }
+
+    pub fn column_words(&self, column: usize) -> Vec<u64> {
+        let mut words = vec![0; self.rows.div_ceil(WORD)];
+        for row in 0..self.rows {
+            if self.get(row, column) {
+                words[row / WORD] |= 1 << (row % WORD);
+            }
+        }
+        words
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn row_count(&self, row: usize) -> usize {
+        self.row_words(row)
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum()
+    }
+
+    // The number of cells that differ between two rows.
+    pub fn row_difference(&self, a: usize, b: usize) -> usize {
+        self.row_words(a)
+            .iter()
+            .zip(self.row_words(b))
+            .map(|(x, y)| (x ^ y).count_ones() as usize)
+            .sum()
+    }
+
+    pub fn xor(&self, other: &BitGrid) -> BitGrid {
+        self.zip_words(other, |a, b| a ^ b)
+    }
+
+    pub fn and(&self, other: &BitGrid) -> BitGrid {
+        self.zip_words(other, |a, b| a & b)
+    }
+
+    pub fn or(&self, other: &BitGrid) -> BitGrid {
+        self.zip_words(other, |a, b| a | b)
+    }
+
+    fn zip_words(&self, other: &BitGrid, f: impl Fn(u64, u64) -> u64) -> BitGrid {
+        assert_eq!(
+            (self.rows, self.columns),
+            (other.rows, other.columns),
+            "Grids must be the same size"
+        );
+        BitGrid {
+            words: self
+                .words
+                .iter()
+                .zip(other.words.iter())
+                .map(|(a, b)| f(*a, *b))
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    pub fn ones(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.rows).flat_map(move |row| {
+            self.row_words(row)
+                .iter()
+                .enumerate()
+                .flat_map(move |(index, word)| {
+                    let mut word = *word;
+                    std::iter::from_fn(move || match word {
+                        0 => None,
+                        _ => {
+                            let bit = word.trailing_zeros() as usize;
+                            word &= word - 1;
+                            Some((row, index * WORD + bit))
+                        }
+                    })
+                })
+        })
+    }
+
+    // Works through 64x64 blocks, so rows and columns swap a word at a time.
+    pub fn transpose(&self) -> BitGrid {
+        let mut transposed = BitGrid::new(self.columns, self.rows);
+        let mut block = [0u64; WORD];
+
+        for row_block in 0..self.rows.div_ceil(WORD) {
+            for column_block in 0..self.stride {
+                for (i, word) in block.iter_mut().enumerate() {
+                    let row = row_block * WORD + i;
+                    *word = match row < self.rows {
+                        true => self.word(row, column_block),
+                        false => 0,
+                    };
+                }
+                transpose_block(&mut block);
+                for (i, word) in block.iter().enumerate() {
+                    let row = column_block * WORD + i;
+                    if row < transposed.rows {
+                        transposed.set_word(row, row_block, *word);
+                    }
+                }
+            }
+        }
+
+        transposed
+    }
+}
+
+// Swaps ever smaller off-diagonal quarters of the block until each bit has
+// moved from (row, column) to (column, row).
+fn transpose_block(block: &mut [u64; WORD]) {
+    let mut width = WORD / 2;
+    let mut mask: u64 = 0x0000_0000_FFFF_FFFF;
+    while width != 0 {
+        let mut k = 0;
+        while k < WORD {
+            let t = ((block[k] >> width) ^ block[k + width]) & mask;
+            block[k] ^= t << width;
+            block[k + width] ^= t;
+            k = (k + width + 1) & !width;
+        }
+        width /= 2;
+        mask ^= mask << width;
+    }
+}
+
+impl Display for BitGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in 0..self.rows {
+            let line: String = (0..self.columns)
+                .map(|column| match self.get(row, column) {
+                    true => '#',
+                    false => '.',
+                })
+                .collect();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+// Cells holding a small enum, stored as one `BitGrid` per bit of its value so
+// each plane can still be worked on a word at a time.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitPlanes {
+    planes: Vec<BitGrid>,
+}
+
+impl BitPlanes {
+    pub fn new(rows: usize, columns: usize, bits: usize) -> BitPlanes {
+        assert!((1..=8).contains(&bits), "Expected between 1 and 8 planes");
+        BitPlanes {
+            planes: vec![BitGrid::new(rows, columns); bits],
+        }
+    }
+
+    pub fn parse(s: &str, bits: usize, cell: impl Fn(char) -> u8) -> BitPlanes {
+        let lines: Vec<&str> = s.lines().filter(|l| !l.is_empty()).collect();
+        let columns = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let mut planes = BitPlanes::new(lines.len(), columns, bits);
+        for (row, line) in lines.iter().enumerate() {
+            for (column, c) in line.chars().enumerate() {
+                planes.set(row, column, cell(c));
+            }
+        }
+        planes
+    }
+
+    pub fn rows(&self) -> usize {
+        self.planes[0].rows()
+    }
+
+    pub fn columns(&self) -> usize {
+        self.planes[0].columns()
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> u8 {
+        self.planes
+            .iter()
+            .enumerate()
+            .fold(0, |value, (bit, plane)| {
+                value | (plane.get(row, column) as u8) << bit
+            })
+    }
+
+    pub fn set(&mut self, row: usize, column: usize, value: u8) {
+        assert!(
+            (value as usize) < 1 << self.planes.len(),
+            "{} doesn't fit in {} planes",
+            value,
+            self.planes.len()
+        );
+        self.planes
+            .iter_mut()
+            .enumerate()
+            .for_each(|(bit, plane)| plane.set(row, column, value >> bit & 1 == 1));
+    }
+
+    pub fn clear(&mut self) {
+        self.planes.iter_mut().for_each(|p| p.clear());
+    }
+
+    pub fn plane(&self, bit: usize) -> &BitGrid {
+        &self.planes[bit]
+    }
+
+    pub fn planes_mut(&mut self) -> &mut [BitGrid] {
+        &mut self.planes
+    }
+
+    // Cells whose value is anything other than zero.
+    pub fn any(&self) -> BitGrid {
+        self.planes[1..]
+            .iter()
+            .fold(self.planes[0].clone(), |acc, p| acc.or(p))
+    }
+
+    pub fn transpose(&self) -> BitPlanes {
+        BitPlanes {
+            planes: self.planes.iter().map(|p| p.transpose()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A fixed xorshift fill, so every block gets a different pattern.
+    fn scattered(rows: usize, columns: usize) -> BitGrid {
+        let mut state: u64 = 0x9E37_79B9_7F4A_7C15 ^ (rows * 1000 + columns) as u64;
+        let mut grid = BitGrid::new(rows, columns);
+        for row in 0..rows {
+            for column in 0..columns {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                grid.set(row, column, state & 1 == 1);
+            }
+        }
+        grid
+    }
+
+    const SIZES: [(usize, usize); 8] = [
+        (1, 1),
+        (3, 70),
+        (64, 64),
+        (63, 65),
+        (65, 63),
+        (100, 7),
+        (130, 129),
+        (0, 5),
+    ];
+
+    #[test]
+    fn transpose_moves_every_cell_across_the_diagonal() {
+        for (rows, columns) in SIZES {
+            let grid = scattered(rows, columns);
+            let transposed = grid.transpose();
+            assert_eq!((transposed.rows(), transposed.columns()), (columns, rows));
+            for row in 0..rows {
+                for column in 0..columns {
+                    assert_eq!(
+                        transposed.get(column, row),
+                        grid.get(row, column),
+                        "{}x{} at ({}, {})",
+                        rows,
+                        columns,
+                        row,
+                        column
+                    );
+                }
+            }
+        }
+    }
+
+    // Equality compares whole words, so this also checks the padding past the
+    // last column is left at zero.
+    #[test]
+    fn transposing_twice_gives_back_the_grid() {
+        for (rows, columns) in SIZES {
+            let grid = scattered(rows, columns);
+            assert_eq!(grid.transpose().transpose(), grid, "{}x{}", rows, columns);
+            assert_eq!(grid.transpose().count_ones(), grid.count_ones());
+        }
+    }
+
+    #[test]
+    fn set_word_masks_bits_past_the_last_column() {
+        let mut grid = BitGrid::new(2, 70);
+        grid.set_word(0, 0, u64::MAX);
+        grid.set_word(0, 1, u64::MAX);
+        assert_eq!(grid.row_count(0), 70);
+        assert_eq!(grid.row_difference(0, 1), 70);
+        assert_eq!(grid.ones().count(), 70);
+    }
+
+    #[test]
+    fn ones_lists_the_set_cells_in_order() {
+        let grid = BitGrid::parse("#..\n...\n.##\n", |c| c == '#');
+        assert_eq!(grid.ones().collect::<Vec<_>>(), [(0, 0), (2, 1), (2, 2)]);
+        assert_eq!(grid.column_words(1), [0b100]);
+    }
+
+    #[test]
+    fn planes_hold_each_bit_of_a_cell() {
+        let mut planes = BitPlanes::new(2, 66, 3);
+        planes.set(1, 65, 5);
+        planes.set(0, 3, 2);
+        assert_eq!(planes.get(1, 65), 5);
+        assert_eq!(planes.get(0, 3), 2);
+        assert_eq!(planes.any().count_ones(), 2);
+        assert_eq!(planes.transpose().get(65, 1), 5);
+    }
+}
@@ -0,0 +1,120 @@
+use std::fmt::Display;
+use std::ops::{Add, Mul, Sub};
+
+// Arithmetic that panics with the operands and the caller's location when the
+// `checked` feature is on, and is plain operator arithmetic otherwise.
+pub trait Checked:
+    Copy + Display + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    const NAME: &'static str;
+    const ZERO: Self;
+
+    fn try_add(self, rhs: Self) -> Option<Self>;
+    fn try_sub(self, rhs: Self) -> Option<Self>;
+    fn try_mul(self, rhs: Self) -> Option<Self>;
+    fn try_pow(self, exp: u32) -> Option<Self>;
+    fn unchecked_pow(self, exp: u32) -> Self;
+}
+
+macro_rules! impl_checked {
+    ($($t:ty),*) => {
+        $(impl Checked for $t {
+            const NAME: &'static str = stringify!($t);
+            const ZERO: Self = 0;
+
+            fn try_add(self, rhs: Self) -> Option<Self> {
+                self.checked_add(rhs)
+            }
+
+            fn try_sub(self, rhs: Self) -> Option<Self> {
+                self.checked_sub(rhs)
+            }
+
+            fn try_mul(self, rhs: Self) -> Option<Self> {
+                self.checked_mul(rhs)
+            }
+
+            fn try_pow(self, exp: u32) -> Option<Self> {
+                self.checked_pow(exp)
+            }
+
+            fn unchecked_pow(self, exp: u32) -> Self {
+                self.pow(exp)
+            }
+        })*
+    };
+}
+
+impl_checked!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+#[cfg(feature = "checked")]
+#[cold]
+#[track_caller]
+fn overflow<T: Checked, U: Display>(lhs: T, op: &str, rhs: U) -> ! {
+    panic!("{} {} {} overflows {}", lhs, op, rhs, T::NAME)
+}
+
+#[cfg(feature = "checked")]
+#[track_caller]
+pub fn add<T: Checked>(lhs: T, rhs: T) -> T {
+    match lhs.try_add(rhs) {
+        Some(result) => result,
+        None => overflow(lhs, "+", rhs),
+    }
+}
+
+#[cfg(feature = "checked")]
+#[track_caller]
+pub fn sub<T: Checked>(lhs: T, rhs: T) -> T {
+    match lhs.try_sub(rhs) {
+        Some(result) => result,
+        None => overflow(lhs, "-", rhs),
+    }
+}
+
+#[cfg(feature = "checked")]
+#[track_caller]
+pub fn mul<T: Checked>(lhs: T, rhs: T) -> T {
+    match lhs.try_mul(rhs) {
+        Some(result) => result,
+        None => overflow(lhs, "*", rhs),
+    }
+}
+
+#[cfg(feature = "checked")]
+#[track_caller]
+pub fn pow<T: Checked>(base: T, exp: u32) -> T {
+    match base.try_pow(exp) {
+        Some(result) => result,
+        None => overflow(base, "^", exp),
+    }
+}
+
+#[cfg(not(feature = "checked"))]
+pub fn add<T: Checked>(lhs: T, rhs: T) -> T {
+    lhs + rhs
+}
+
+#[cfg(not(feature = "checked"))]
+pub fn sub<T: Checked>(lhs: T, rhs: T) -> T {
+    lhs - rhs
+}
+
+#[cfg(not(feature = "checked"))]
+pub fn mul<T: Checked>(lhs: T, rhs: T) -> T {
+    lhs * rhs
+}
+
+#[cfg(not(feature = "checked"))]
+pub fn pow<T: Checked>(base: T, exp: u32) -> T {
+    base.unchecked_pow(exp)
+}
+
+#[track_caller]
+pub fn sum<T: Checked>(values: impl IntoIterator<Item = T>) -> T {
+    let mut total = T::ZERO;
+    for value in values {
+        total = add(total, value);
+    }
+    total
+}
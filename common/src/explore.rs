@@ -0,0 +1,245 @@
+use std::env;
+
+pub trait Explorable {
+    fn size(&self) -> (usize, usize);
+    fn glyph(&self, row: usize, column: usize) -> char;
+
+    fn describe(&self, _row: usize, _column: usize) -> String {
+        String::new()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Mark {
+    pub row: usize,
+    pub column: usize,
+    pub glyph: char,
+    pub note: String,
+}
+
+// An overlay is a sequence of steps, each marking some cells. Stepping
+// through it replays the steps in order, later marks replacing earlier ones.
+#[derive(Debug, Clone, Default)]
+pub struct Overlay {
+    pub name: String,
+    pub steps: Vec<Vec<Mark>>,
+}
+
+impl Overlay {
+    pub fn new(name: &str) -> Overlay {
+        Overlay {
+            name: name.to_string(),
+            steps: vec![],
+        }
+    }
+
+    pub fn step(&mut self, marks: Vec<Mark>) {
+        self.steps.push(marks);
+    }
+
+    pub fn mark(&mut self, row: usize, column: usize, glyph: char, note: String) {
+        self.steps.push(vec![Mark {
+            row,
+            column,
+            glyph,
+            note,
+        }]);
+    }
+
+    #[cfg(feature = "explore")]
+    fn marks_at(&self, step: usize) -> std::collections::HashMap<(usize, usize), &Mark> {
+        self.steps
+            .iter()
+            .take(step + 1)
+            .flatten()
+            .map(|m| ((m.row, m.column), m))
+            .collect()
+    }
+}
+
+pub fn requested() -> bool {
+    env::args().any(|a| a == "--explore")
+}
+
+#[cfg(feature = "explore")]
+mod terminal {
+    use super::{Explorable, Overlay};
+    use crossterm::cursor::{Hide, MoveTo, Show};
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+    use crossterm::style::{Attribute, Color, Print, SetAttribute, SetForegroundColor};
+    use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+    use crossterm::{execute, queue};
+    use std::io::{self, Write};
+
+    const STATUS_LINES: u16 = 3;
+
+    struct State {
+        row: usize,
+        column: usize,
+        overlay: usize,
+        step: usize,
+    }
+
+    impl State {
+        fn last_step(&self, overlays: &[Overlay]) -> usize {
+            overlays
+                .get(self.overlay)
+                .map(|o| o.steps.len().saturating_sub(1))
+                .unwrap_or(0)
+        }
+    }
+
+    fn origin(cursor: usize, extent: usize, visible: usize) -> usize {
+        if extent <= visible {
+            0
+        } else {
+            cursor.saturating_sub(visible / 2).min(extent - visible)
+        }
+    }
+
+    fn draw<E: Explorable>(
+        out: &mut impl Write,
+        model: &E,
+        overlays: &[Overlay],
+        state: &State,
+    ) -> io::Result<()> {
+        let (rows, columns) = model.size();
+        let (width, height) = terminal::size()?;
+        let visible_rows = height.saturating_sub(STATUS_LINES) as usize;
+        let visible_columns = width as usize;
+        let top = origin(state.row, rows, visible_rows);
+        let left = origin(state.column, columns, visible_columns);
+
+        let overlay = overlays.get(state.overlay);
+        let marks = overlay.map(|o| o.marks_at(state.step)).unwrap_or_default();
+
+        queue!(out, Clear(ClearType::All))?;
+        for row in top..rows.min(top + visible_rows) {
+            queue!(out, MoveTo(0, (row - top) as u16))?;
+            for column in left..columns.min(left + visible_columns) {
+                let is_cursor = row == state.row && column == state.column;
+                if is_cursor {
+                    queue!(out, SetAttribute(Attribute::Reverse))?;
+                }
+                match marks.get(&(row, column)) {
+                    Some(mark) => queue!(
+                        out,
+                        SetForegroundColor(Color::Yellow),
+                        Print(mark.glyph),
+                        SetForegroundColor(Color::Reset)
+                    )?,
+                    None => queue!(out, Print(model.glyph(row, column)))?,
+                }
+                if is_cursor {
+                    queue!(out, SetAttribute(Attribute::Reset))?;
+                }
+            }
+        }
+
+        let status_top = visible_rows.min(rows) as u16;
+        let overlay_status = match overlay {
+            Some(o) => format!(
+                "Overlay {}/{}: {} | Step {}/{}",
+                state.overlay + 1,
+                overlays.len(),
+                o.name,
+                state.step + 1,
+                o.steps.len()
+            ),
+            None => "No overlays".to_string(),
+        };
+        let note = marks
+            .get(&(state.row, state.column))
+            .map(|m| format!(" | {}", m.note))
+            .unwrap_or_default();
+
+        queue!(
+            out,
+            MoveTo(0, status_top),
+            Print(format!(
+                "({}, {}) {} {}{}",
+                state.row,
+                state.column,
+                model.glyph(state.row, state.column),
+                model.describe(state.row, state.column),
+                note
+            )),
+            MoveTo(0, status_top + 1),
+            Print(overlay_status),
+            MoveTo(0, status_top + 2),
+            Print("arrows/hjkl move, tab overlay, n/p step, N/P step x100, g/G first/last, q quit")
+        )?;
+        out.flush()
+    }
+
+    fn handle(key: KeyCode, model_size: (usize, usize), overlays: &[Overlay], state: &mut State) {
+        let (rows, columns) = model_size;
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => state.row = state.row.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => state.row = (state.row + 1).min(rows - 1),
+            KeyCode::Left | KeyCode::Char('h') => state.column = state.column.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => {
+                state.column = (state.column + 1).min(columns - 1)
+            }
+            KeyCode::Tab if !overlays.is_empty() => {
+                state.overlay = (state.overlay + 1) % overlays.len();
+                state.step = state.last_step(overlays);
+            }
+            KeyCode::Char('n') => state.step = (state.step + 1).min(state.last_step(overlays)),
+            KeyCode::Char('p') => state.step = state.step.saturating_sub(1),
+            KeyCode::Char('N') => state.step = (state.step + 100).min(state.last_step(overlays)),
+            KeyCode::Char('P') => state.step = state.step.saturating_sub(100),
+            KeyCode::Char('g') => state.step = 0,
+            KeyCode::Char('G') => state.step = state.last_step(overlays),
+            _ => (),
+        }
+    }
+
+    pub fn explore<E: Explorable>(model: &E, overlays: &[Overlay]) -> io::Result<()> {
+        let (rows, columns) = model.size();
+        if rows == 0 || columns == 0 {
+            return Ok(());
+        }
+
+        let mut state = State {
+            row: 0,
+            column: 0,
+            overlay: 0,
+            step: 0,
+        };
+        state.step = state.last_step(overlays);
+
+        let mut out = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(out, EnterAlternateScreen, Hide)?;
+
+        let result = (|| loop {
+            draw(&mut out, model, overlays, &state)?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    code => handle(code, (rows, columns), overlays, &mut state),
+                }
+            }
+        })();
+
+        execute!(out, Show, LeaveAlternateScreen)?;
+        terminal::disable_raw_mode()?;
+        result
+    }
+}
+
+#[cfg(feature = "explore")]
+pub fn explore<E: Explorable>(model: &E, overlays: &[Overlay]) {
+    if let Err(e) = terminal::explore(model, overlays) {
+        eprintln!("Explorer failed: {}", e);
+    }
+}
+
+#[cfg(not(feature = "explore"))]
+pub fn explore<E: Explorable>(_model: &E, _overlays: &[Overlay]) {
+    eprintln!("Explorer unavailable, rebuild with --features explore");
+}
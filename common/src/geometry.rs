@@ -0,0 +1,401 @@
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{Num, Signed, Zero};
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::ops::{Add, Neg, Sub};
+use std::str::FromStr;
+
+pub type Rational = BigRational;
+
+pub fn rational(n: i64) -> Rational {
+    BigRational::from_integer(BigInt::from(n))
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Vec3<T> {
+    pub fn new(x: T, y: T, z: T) -> Vec3<T> {
+        Vec3 { x, y, z }
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Vec3<U> {
+        Vec3::new(f(&self.x), f(&self.y), f(&self.z))
+    }
+}
+
+impl<T: Num + Clone> Vec3<T> {
+    pub fn zero() -> Vec3<T> {
+        Vec3::new(T::zero(), T::zero(), T::zero())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.x.is_zero() && self.y.is_zero() && self.z.is_zero()
+    }
+
+    pub fn dot(&self, other: &Vec3<T>) -> T {
+        self.x.clone() * other.x.clone()
+            + self.y.clone() * other.y.clone()
+            + self.z.clone() * other.z.clone()
+    }
+
+    pub fn cross(&self, other: &Vec3<T>) -> Vec3<T> {
+        Vec3::new(
+            self.y.clone() * other.z.clone() - self.z.clone() * other.y.clone(),
+            self.z.clone() * other.x.clone() - self.x.clone() * other.z.clone(),
+            self.x.clone() * other.y.clone() - self.y.clone() * other.x.clone(),
+        )
+    }
+
+    pub fn scale(&self, k: &T) -> Vec3<T> {
+        self.map(|c| c.clone() * k.clone())
+    }
+}
+
+impl Vec3<i64> {
+    pub fn to_rational(&self) -> Vec3<Rational> {
+        self.map(|c| rational(*c))
+    }
+}
+
+impl<T: Num + Clone> Add for Vec3<T> {
+    type Output = Vec3<T>;
+    fn add(self, rhs: Self) -> Self::Output {
+        Vec3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Num + Clone> Sub for Vec3<T> {
+    type Output = Vec3<T>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Vec3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Num + Clone + Neg<Output = T>> Neg for Vec3<T> {
+    type Output = Vec3<T>;
+    fn neg(self) -> Self::Output {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Display> Display for Vec3<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}, {}, {}", self.x, self.y, self.z)
+    }
+}
+
+impl<T: FromStr> FromStr for Vec3<T> {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut components = s.split(',').map(|c| c.trim().parse::<T>().map_err(|_| ()));
+        match (
+            components.next(),
+            components.next(),
+            components.next(),
+            components.next(),
+        ) {
+            (Some(x), Some(y), Some(z), None) => Ok(Vec3::new(x?, y?, z?)),
+            _ => Err(()),
+        }
+    }
+}
+
+// The points `point + t * direction` for every rational `t`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+    pub point: Vec3<Rational>,
+    pub direction: Vec3<Rational>,
+}
+
+impl Line {
+    pub fn new(point: Vec3<Rational>, direction: Vec3<Rational>) -> Line {
+        Line { point, direction }
+    }
+
+    pub fn at(&self, t: &Rational) -> Vec3<Rational> {
+        self.point.clone() + self.direction.scale(t)
+    }
+
+    // Drops the z components, for questions about the xy plane.
+    pub fn flattened(&self) -> Line {
+        Line::new(
+            Vec3::new(self.point.x.clone(), self.point.y.clone(), Rational::zero()),
+            Vec3::new(
+                self.direction.x.clone(),
+                self.direction.y.clone(),
+                Rational::zero(),
+            ),
+        )
+    }
+
+    pub fn is_parallel(&self, other: &Line) -> bool {
+        self.direction.cross(&other.direction).is_zero()
+    }
+
+    pub fn contains(&self, point: &Vec3<Rational>) -> bool {
+        self.time_of(point).is_some()
+    }
+
+    // The `t` at which the line passes through `point`.
+    pub fn time_of(&self, point: &Vec3<Rational>) -> Option<Rational> {
+        let offset = point.clone() - self.point.clone();
+        let t = [
+            (&offset.x, &self.direction.x),
+            (&offset.y, &self.direction.y),
+            (&offset.z, &self.direction.z),
+        ]
+        .into_iter()
+        .find(|(_, d)| !d.is_zero())
+        .map(|(o, d)| o / d)
+        .unwrap_or_else(Rational::zero);
+
+        match self.at(&t) == *point {
+            true => Some(t),
+            false => None,
+        }
+    }
+
+    // The times along `self` and `other` at which they meet, or `None` when
+    // they are parallel or skew.
+    pub fn crossing(&self, other: &Line) -> Option<(Rational, Rational)> {
+        let normal = self.direction.cross(&other.direction);
+        let denominator = normal.dot(&normal);
+        if denominator.is_zero() {
+            return None;
+        }
+
+        let offset = other.point.clone() - self.point.clone();
+        let t = offset.cross(&other.direction).dot(&normal) / denominator.clone();
+        let s = offset.cross(&self.direction).dot(&normal) / denominator;
+
+        match self.at(&t) == other.at(&s) {
+            true => Some((t, s)),
+            false => None,
+        }
+    }
+
+    pub fn intersection(&self, other: &Line) -> Option<Vec3<Rational>> {
+        self.crossing(other).map(|(t, _)| self.at(&t))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub start: Vec3<Rational>,
+    pub end: Vec3<Rational>,
+}
+
+impl Segment {
+    pub fn new(start: Vec3<Rational>, end: Vec3<Rational>) -> Segment {
+        Segment { start, end }
+    }
+
+    // Runs from `start` at t = 0 to `end` at t = 1.
+    pub fn line(&self) -> Line {
+        Line::new(self.start.clone(), self.end.clone() - self.start.clone())
+    }
+
+    pub fn is_parallel(&self, other: &Segment) -> bool {
+        self.line().is_parallel(&other.line())
+    }
+
+    pub fn contains(&self, point: &Vec3<Rational>) -> bool {
+        self.line().time_of(point).is_some_and(|t| within_unit(&t))
+    }
+
+    pub fn is_point(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn intersection(&self, other: &Segment) -> Option<Intersection> {
+        let line = self.line();
+        if self.is_point() || other.is_point() {
+            let (point, segment) = match self.is_point() {
+                true => (&self.start, other),
+                false => (&other.start, self),
+            };
+            return segment
+                .contains(point)
+                .then(|| Intersection::Point(point.clone()));
+        }
+        if !self.is_parallel(other) {
+            return match line.crossing(&other.line()) {
+                Some((t, s)) if within_unit(&t) && within_unit(&s) => {
+                    Some(Intersection::Point(line.at(&t)))
+                }
+                _ => None,
+            };
+        }
+
+        // Collinear segments share whatever part of `other` falls in 0..=1
+        // along `self`.
+        let (a, b) = match (line.time_of(&other.start), line.time_of(&other.end)) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return None,
+        };
+        let first = a.clone().min(b.clone()).max(rational(0));
+        let last = a.max(b).min(rational(1));
+        match first.cmp(&last) {
+            Ordering::Greater => None,
+            Ordering::Equal => Some(Intersection::Point(line.at(&first))),
+            Ordering::Less => Some(Intersection::Overlap(Segment::new(
+                line.at(&first),
+                line.at(&last),
+            ))),
+        }
+    }
+}
+
+// Where two segments meet: a single point, or the stretch that collinear
+// segments have in common, running the same way as the first segment.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Intersection {
+    Point(Vec3<Rational>),
+    Overlap(Segment),
+}
+
+fn within_unit(t: &Rational) -> bool {
+    !t.is_negative() && *t <= rational(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(x: i64, y: i64, z: i64) -> Vec3<Rational> {
+        Vec3::new(x, y, z).to_rational()
+    }
+
+    fn segment(start: (i64, i64, i64), end: (i64, i64, i64)) -> Segment {
+        Segment::new(point(start.0, start.1, start.2), point(end.0, end.1, end.2))
+    }
+
+    #[test]
+    fn vec3_products_and_parsing() {
+        let a = Vec3::new(1, 2, 3);
+        let b = Vec3::new(4, -5, 6);
+        assert_eq!(a.dot(&b), 12);
+        assert_eq!(a.cross(&b), Vec3::new(27, 6, -13));
+        assert!(a.cross(&a.scale(&3)).is_zero());
+        assert_eq!(
+            "19, 13, -30".parse::<Vec3<i64>>(),
+            Ok(Vec3::new(19, 13, -30))
+        );
+        assert!("1, 2".parse::<Vec3<i64>>().is_err());
+        assert!("1, 2, 3, 4".parse::<Vec3<i64>>().is_err());
+    }
+
+    #[test]
+    fn lines_cross_once_unless_parallel_or_skew() {
+        let x_axis = Line::new(point(0, 0, 0), point(1, 0, 0));
+        let diagonal = Line::new(point(0, -2, 0), point(1, 1, 0));
+        assert_eq!(x_axis.intersection(&diagonal), Some(point(2, 0, 0)));
+        assert_eq!(x_axis.crossing(&diagonal), Some((rational(2), rational(2))));
+
+        let parallel = Line::new(point(0, 1, 0), point(-2, 0, 0));
+        assert!(x_axis.is_parallel(&parallel));
+        assert_eq!(x_axis.crossing(&parallel), None);
+
+        let skew = Line::new(point(0, 0, 1), point(0, 1, 0));
+        assert!(!x_axis.is_parallel(&skew));
+        assert_eq!(x_axis.crossing(&skew), None);
+        assert_eq!(
+            x_axis.flattened().intersection(&skew.flattened()),
+            Some(point(0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn time_of_finds_points_on_the_line_only() {
+        let line = Line::new(point(1, 1, 1), point(2, 0, -2));
+        assert_eq!(
+            line.time_of(&point(4, 1, -2)),
+            Some(Rational::new(3.into(), 2.into()))
+        );
+        assert_eq!(line.time_of(&point(4, 2, -2)), None);
+        assert!(line.contains(&point(-1, 1, 3)));
+    }
+
+    #[test]
+    fn crossing_segments_meet_inside_both() {
+        let a = segment((0, 0, 0), (4, 4, 0));
+        let b = segment((0, 4, 0), (4, 0, 0));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Intersection::Point(point(2, 2, 0)))
+        );
+
+        // The lines cross, but past the end of the shorter segment.
+        let short = segment((0, 4, 0), (1, 3, 0));
+        assert_eq!(a.intersection(&short), None);
+    }
+
+    #[test]
+    fn touching_segments_meet_at_the_shared_end() {
+        let a = segment((0, 0, 0), (2, 0, 0));
+        let b = segment((2, 0, 0), (2, 5, 0));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Intersection::Point(point(2, 0, 0)))
+        );
+
+        let collinear = segment((2, 0, 0), (7, 0, 0));
+        assert_eq!(
+            a.intersection(&collinear),
+            Some(Intersection::Point(point(2, 0, 0)))
+        );
+    }
+
+    #[test]
+    fn parallel_segments_apart_never_meet() {
+        let a = segment((0, 0, 0), (2, 0, 0));
+        let b = segment((0, 1, 0), (2, 1, 0));
+        assert!(a.is_parallel(&b));
+        assert_eq!(a.intersection(&b), None);
+
+        let beyond = segment((3, 0, 0), (5, 0, 0));
+        assert_eq!(a.intersection(&beyond), None);
+    }
+
+    #[test]
+    fn collinear_segments_share_their_overlap() {
+        let a = segment((0, 0, 0), (4, 4, 4));
+        let b = segment((6, 6, 6), (2, 2, 2));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Intersection::Overlap(segment((2, 2, 2), (4, 4, 4))))
+        );
+        assert_eq!(
+            b.intersection(&a),
+            Some(Intersection::Overlap(segment((4, 4, 4), (2, 2, 2))))
+        );
+
+        let inside = segment((1, 1, 1), (3, 3, 3));
+        assert_eq!(
+            a.intersection(&inside),
+            Some(Intersection::Overlap(inside.clone()))
+        );
+    }
+
+    #[test]
+    fn a_point_meets_the_segments_through_it() {
+        let a = segment((0, 0, 0), (4, 0, 0));
+        let on = segment((1, 0, 0), (1, 0, 0));
+        let off = segment((1, 1, 0), (1, 1, 0));
+        assert_eq!(
+            a.intersection(&on),
+            Some(Intersection::Point(point(1, 0, 0)))
+        );
+        assert_eq!(
+            on.intersection(&a),
+            Some(Intersection::Point(point(1, 0, 0)))
+        );
+        assert_eq!(a.intersection(&off), None);
+    }
+}
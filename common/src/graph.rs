@@ -0,0 +1,253 @@
+use std::collections::{BTreeSet, HashSet};
+use std::env;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Dot,
+    Mermaid,
+}
+
+pub fn requested() -> Option<Format> {
+    env::args().find_map(|a| match a.as_str() {
+        "--dot" => Some(Format::Dot),
+        "--mermaid" => Some(Format::Mermaid),
+        _ => None,
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub id: String,
+    pub label: String,
+    pub kind: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub label: String,
+}
+
+pub trait ExportGraph {
+    fn nodes(&self) -> Vec<Node>;
+    fn edges(&self) -> Vec<Edge>;
+
+    fn directed(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Highlight {
+    nodes: HashSet<String>,
+    edges: HashSet<(String, String)>,
+}
+
+impl Highlight {
+    pub fn path(ids: &[String]) -> Highlight {
+        Highlight {
+            nodes: ids.iter().cloned().collect(),
+            edges: ids
+                .windows(2)
+                .map(|w| (w[0].clone(), w[1].clone()))
+                .collect(),
+        }
+    }
+
+    pub fn cycle(ids: &[String]) -> Highlight {
+        let mut highlight = Highlight::path(ids);
+        if let (Some(first), Some(last)) = (ids.first(), ids.last()) {
+            highlight.edges.insert((last.clone(), first.clone()));
+        }
+        highlight
+    }
+
+    pub fn cut(edges: &[(String, String)]) -> Highlight {
+        Highlight {
+            nodes: HashSet::new(),
+            edges: edges.iter().cloned().collect(),
+        }
+    }
+
+    fn has_edge(&self, from: &str, to: &str, directed: bool) -> bool {
+        let key = (from.to_string(), to.to_string());
+        self.edges.contains(&key)
+            || (!directed && self.edges.contains(&(key.1.clone(), key.0.clone())))
+    }
+}
+
+const HIGHLIGHT_COLOUR: &str = "#e31a1c";
+const DOT_SHAPES: [&str; 6] = [
+    "ellipse", "box", "diamond", "hexagon", "octagon", "triangle",
+];
+const COLOURS: [&str; 6] = [
+    "#a6cee3", "#b2df8a", "#fdbf6f", "#cab2d6", "#ffff99", "#fb9a99",
+];
+
+// Nodes and edges are sorted so the same graph always renders to the same
+// text, and undirected edges are only drawn once.
+fn sorted<G: ExportGraph>(graph: &G) -> (Vec<Node>, Vec<Edge>, Vec<String>) {
+    let mut nodes = graph.nodes();
+    nodes.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut edges = graph.edges();
+    if !graph.directed() {
+        edges.iter_mut().for_each(|e| {
+            if e.from > e.to {
+                std::mem::swap(&mut e.from, &mut e.to);
+            }
+        });
+    }
+    edges.sort_by(|a, b| (&a.from, &a.to, &a.label).cmp(&(&b.from, &b.to, &b.label)));
+    edges.dedup_by(|a, b| a.from == b.from && a.to == b.to && a.label == b.label);
+
+    let kinds: BTreeSet<String> = nodes.iter().map(|n| n.kind.clone()).collect();
+    (nodes, edges, kinds.into_iter().collect())
+}
+
+fn style_index(kinds: &[String], kind: &str) -> usize {
+    kinds.iter().position(|k| k == kind).unwrap_or(0)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn dot<G: ExportGraph>(graph: &G, highlight: &Highlight) -> String {
+    let (nodes, edges, kinds) = sorted(graph);
+    let (keyword, arrow) = match graph.directed() {
+        true => ("digraph", "->"),
+        false => ("graph", "--"),
+    };
+
+    let mut lines = vec![format!("{} {{", keyword), "    rankdir=LR;".to_string()];
+    for node in nodes.iter() {
+        let style = style_index(&kinds, &node.kind);
+        let mut attributes = format!(
+            "label=\"{}\", shape={}, style=filled, fillcolor=\"{}\"",
+            escape(&node.label),
+            DOT_SHAPES[style % DOT_SHAPES.len()],
+            COLOURS[style % COLOURS.len()]
+        );
+        if highlight.nodes.contains(&node.id) {
+            attributes += &format!(", color=\"{}\", penwidth=3", HIGHLIGHT_COLOUR);
+        }
+        lines.push(format!("    \"{}\" [{}];", escape(&node.id), attributes));
+    }
+
+    for edge in edges.iter() {
+        let mut attributes = vec![];
+        if !edge.label.is_empty() {
+            attributes.push(format!("label=\"{}\"", escape(&edge.label)));
+        }
+        if highlight.has_edge(&edge.from, &edge.to, graph.directed()) {
+            attributes.push(format!("color=\"{}\", penwidth=3", HIGHLIGHT_COLOUR));
+        }
+        let attributes = match attributes.is_empty() {
+            true => String::new(),
+            false => format!(" [{}]", attributes.join(", ")),
+        };
+        lines.push(format!(
+            "    \"{}\" {} \"{}\"{};",
+            escape(&edge.from),
+            arrow,
+            escape(&edge.to),
+            attributes
+        ));
+    }
+
+    lines.push("}".to_string());
+    lines.join("\n") + "\n"
+}
+
+// Mermaid ids can't contain most punctuation and `end` is a keyword.
+fn mermaid_id(id: &str) -> String {
+    let safe: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("n_{}", safe)
+}
+
+fn mermaid<G: ExportGraph>(graph: &G, highlight: &Highlight) -> String {
+    let (nodes, edges, kinds) = sorted(graph);
+    let arrow = match graph.directed() {
+        true => "-->",
+        false => "---",
+    };
+
+    let mut lines = vec!["flowchart LR".to_string()];
+    for node in nodes.iter() {
+        let label = node.label.replace('"', "#quot;");
+        let shape = match style_index(&kinds, &node.kind) % DOT_SHAPES.len() {
+            0 => format!("([\"{}\"])", label),
+            1 => format!("[\"{}\"]", label),
+            2 => format!("{{\"{}\"}}", label),
+            3 => format!("{{{{\"{}\"}}}}", label),
+            4 => format!("[[\"{}\"]]", label),
+            _ => format!(">\"{}\"]", label),
+        };
+        lines.push(format!("    {}{}", mermaid_id(&node.id), shape));
+    }
+
+    let mut highlighted_links = vec![];
+    for (i, edge) in edges.iter().enumerate() {
+        let link = match edge.label.is_empty() {
+            true => arrow.to_string(),
+            false => format!("{}|\"{}\"|", arrow, edge.label.replace('"', "#quot;")),
+        };
+        lines.push(format!(
+            "    {} {} {}",
+            mermaid_id(&edge.from),
+            link,
+            mermaid_id(&edge.to)
+        ));
+        if highlight.has_edge(&edge.from, &edge.to, graph.directed()) {
+            highlighted_links.push(i.to_string());
+        }
+    }
+
+    for (i, kind) in kinds.iter().enumerate() {
+        let members: Vec<String> = nodes
+            .iter()
+            .filter(|n| n.kind == *kind)
+            .map(|n| mermaid_id(&n.id))
+            .collect();
+        lines.push(format!(
+            "    classDef kind{} fill:{}",
+            i,
+            COLOURS[i % COLOURS.len()]
+        ));
+        lines.push(format!("    class {} kind{}", members.join(","), i));
+    }
+
+    let highlighted_nodes: Vec<String> = nodes
+        .iter()
+        .filter(|n| highlight.nodes.contains(&n.id))
+        .map(|n| mermaid_id(&n.id))
+        .collect();
+    if !highlighted_nodes.is_empty() {
+        lines.push(format!(
+            "    style {} stroke:{},stroke-width:3px",
+            highlighted_nodes.join(","),
+            HIGHLIGHT_COLOUR
+        ));
+    }
+    if !highlighted_links.is_empty() {
+        lines.push(format!(
+            "    linkStyle {} stroke:{},stroke-width:3px",
+            highlighted_links.join(","),
+            HIGHLIGHT_COLOUR
+        ));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+pub fn render<G: ExportGraph>(graph: &G, format: Format, highlight: &Highlight) -> String {
+    match format {
+        Format::Dot => dot(graph, highlight),
+        Format::Mermaid => mermaid(graph, highlight),
+    }
+}
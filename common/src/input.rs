@@ -0,0 +1,26 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+
+// The runner points `AOC_INPUT` at an example file to check a solution
+// against known answers without touching the real input.
+fn input_path(input_name: &str) -> String {
+    env::var("AOC_INPUT").unwrap_or_else(|_| format!("inputs/{}", input_name))
+}
+
+pub fn load_input(input_name: &str) -> String {
+    let path = input_path(input_name);
+
+    fs::read_to_string(&path).unwrap_or_else(|e| panic!("Couldn't read input {}: {}", path, e))
+}
+
+// The same input a line at a time, for solutions that don't need it all in
+// memory at once.
+pub fn read_lines(input_name: &str) -> impl Iterator<Item = String> {
+    let path = input_path(input_name);
+    let file = File::open(&path).unwrap_or_else(|e| panic!("Couldn't read input {}: {}", path, e));
+
+    BufReader::new(file)
+        .lines()
+        .map(move |l| l.unwrap_or_else(|e| panic!("Couldn't read input {}: {}", path, e)))
+}
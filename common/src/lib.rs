@@ -0,0 +1,12 @@
+pub mod bitgrid;
+pub mod checked;
+pub mod explore;
+pub mod geometry;
+pub mod graph;
+pub mod input;
+pub mod linear;
+pub mod output;
+pub mod parallel;
+pub mod params;
+pub mod profile;
+pub mod render;
@@ -0,0 +1,200 @@
+use crate::geometry::Rational;
+use num_traits::Zero;
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Solution {
+    Unique(Vec<Rational>),
+    Underdetermined { rank: usize, unknowns: usize },
+    Inconsistent { rank: usize },
+}
+
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Solution::Unique(values) => write!(
+                f,
+                "unique solution [{}]",
+                values
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Solution::Underdetermined { rank, unknowns } => write!(
+                f,
+                "underdetermined, rank {} with {} unknowns",
+                rank, unknowns
+            ),
+            Solution::Inconsistent { rank } => {
+                write!(f, "inconsistent, equations contradict at rank {}", rank)
+            }
+        }
+    }
+}
+
+// A system of linear equations, solved exactly by Gaussian elimination.
+// Extra equations are fine as long as they agree with the others.
+#[derive(Debug, Clone)]
+pub struct System {
+    unknowns: usize,
+    rows: Vec<Vec<Rational>>,
+}
+
+impl System {
+    pub fn new(unknowns: usize) -> System {
+        System {
+            unknowns,
+            rows: vec![],
+        }
+    }
+
+    pub fn equation(&mut self, coefficients: Vec<Rational>, constant: Rational) {
+        assert_eq!(
+            coefficients.len(),
+            self.unknowns,
+            "Expected one coefficient per unknown"
+        );
+        let mut row = coefficients;
+        row.push(constant);
+        self.rows.push(row);
+    }
+
+    // Reduces the augmented matrix to row echelon form, returning it with
+    // the pivot column of each leading row.
+    fn eliminate(&self) -> (Vec<Vec<Rational>>, Vec<usize>) {
+        let mut rows = self.rows.clone();
+        let mut pivots = vec![];
+
+        for column in 0..self.unknowns {
+            let rank = pivots.len();
+            let Some(pivot) = (rank..rows.len()).find(|r| !rows[*r][column].is_zero()) else {
+                continue;
+            };
+            rows.swap(rank, pivot);
+
+            let lead = rows[rank][column].clone();
+            rows[rank]
+                .iter_mut()
+                .for_each(|v| *v = v.clone() / lead.clone());
+
+            let pivot_row = rows[rank].clone();
+            for (r, row) in rows.iter_mut().enumerate() {
+                if r == rank || row[column].is_zero() {
+                    continue;
+                }
+                let factor = row[column].clone();
+                row.iter_mut()
+                    .zip(pivot_row.iter())
+                    .skip(column)
+                    .for_each(|(v, p)| *v = v.clone() - factor.clone() * p.clone());
+            }
+            pivots.push(column);
+        }
+
+        (rows, pivots)
+    }
+
+    pub fn rank(&self) -> usize {
+        self.eliminate().1.len()
+    }
+
+    pub fn solve(&self) -> Solution {
+        let (rows, pivots) = self.eliminate();
+        let rank = pivots.len();
+
+        if rows
+            .iter()
+            .skip(rank)
+            .any(|row| !row[self.unknowns].is_zero())
+        {
+            return Solution::Inconsistent { rank };
+        }
+
+        if rank < self.unknowns {
+            return Solution::Underdetermined {
+                rank,
+                unknowns: self.unknowns,
+            };
+        }
+
+        Solution::Unique(
+            rows.iter()
+                .take(rank)
+                .map(|row| row[self.unknowns].clone())
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::rational;
+
+    fn system(unknowns: usize, equations: &[(&[i64], i64)]) -> System {
+        let mut system = System::new(unknowns);
+        for (coefficients, constant) in equations {
+            system.equation(
+                coefficients.iter().map(|c| rational(*c)).collect(),
+                rational(*constant),
+            );
+        }
+        system
+    }
+
+    #[test]
+    fn unique_solutions_are_exact() {
+        // The first column starts at zero, so a row has to be swapped up.
+        let solution = system(3, &[(&[0, 2, 1], 4), (&[1, 1, 1], 3), (&[2, 0, 3], 4)]).solve();
+        assert_eq!(
+            solution,
+            Solution::Unique(vec![
+                Rational::new(1.into(), 2.into()),
+                Rational::new(3.into(), 2.into()),
+                rational(1),
+            ])
+        );
+    }
+
+    #[test]
+    fn contradicting_equations_are_inconsistent() {
+        let solution = system(2, &[(&[1, 1], 2), (&[2, 2], 5)]).solve();
+        assert_eq!(solution, Solution::Inconsistent { rank: 1 });
+    }
+
+    #[test]
+    fn too_few_independent_equations_are_underdetermined() {
+        let solution = system(3, &[(&[1, 2, 3], 6), (&[2, 4, 6], 12)]).solve();
+        assert_eq!(
+            solution,
+            Solution::Underdetermined {
+                rank: 1,
+                unknowns: 3
+            }
+        );
+        assert_eq!(
+            System::new(2).solve(),
+            Solution::Underdetermined {
+                rank: 0,
+                unknowns: 2
+            }
+        );
+    }
+
+    #[test]
+    fn redundant_rows_in_an_overdetermined_system_still_solve() {
+        let agreeing = system(
+            2,
+            &[(&[1, 1], 3), (&[1, -1], 1), (&[2, 2], 6), (&[3, 1], 7)],
+        );
+        assert_eq!(agreeing.rank(), 2);
+        assert_eq!(
+            agreeing.solve(),
+            Solution::Unique(vec![rational(2), rational(1)])
+        );
+
+        let extra = system(2, &[(&[1, 1], 3), (&[1, -1], 1), (&[3, 1], 8)]);
+        assert_eq!(extra.solve(), Solution::Inconsistent { rank: 2 });
+    }
+}
@@ -0,0 +1,99 @@
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::cell::RefCell;
+use std::env;
+use std::fmt::Display;
+use std::mem;
+use std::time::Duration;
+
+// What has been recorded since the last line was printed.
+#[derive(Debug, Default)]
+struct Report {
+    answers: Vec<(u32, Value)>,
+    details: Map<String, Value>,
+}
+
+thread_local! {
+    static REPORT: RefCell<Report> = RefCell::new(Report::default());
+}
+
+// `--format json` or `--format=json`; anything else keeps the text output.
+pub fn json_requested() -> bool {
+    let args: Vec<String> = env::args().collect();
+    args.iter().any(|a| a == "--format=json")
+        || args
+            .windows(2)
+            .any(|w| w[0] == "--format" && w[1] == "json")
+}
+
+pub fn answer(part: u32, value: impl Display) {
+    let value = value.to_string();
+    if !json_requested() {
+        println!("Part {} Result: {}", part, value);
+        return;
+    }
+
+    // Only integers written the way JSON writes them are bare numbers; `+5`,
+    // `007` or anything too wide for JSON's integers stays a string.
+    let json = match (value.parse::<i64>(), value.parse::<u64>()) {
+        (Ok(n), _) if n.to_string() == value => Value::from(n),
+        (_, Ok(n)) if n.to_string() == value => Value::from(n),
+        _ => Value::String(value),
+    };
+    REPORT.with_borrow_mut(|r| r.answers.push((part, json)));
+}
+
+// `value` is only built when JSON was asked for, so details can be costly.
+pub fn detail<T: Serialize>(name: &str, value: impl FnOnce() -> T) {
+    if json_requested() {
+        let json = serde_json::to_value(value())
+            .unwrap_or_else(|e| panic!("Detail {} can't be written as JSON: {}", name, e));
+        REPORT.with_borrow_mut(|r| r.details.insert(name.to_string(), json));
+    }
+}
+
+// In a JSON run each part prints its line as soon as it's done, so the
+// runner sees it before a later part runs out of time or memory.
+pub(crate) fn timed(part: u32, elapsed: Duration) {
+    if !json_requested() {
+        return;
+    }
+
+    let line = REPORT.with_borrow_mut(|r| {
+        let answer = r
+            .answers
+            .iter()
+            .position(|(p, _)| *p == part)
+            .map(|i| r.answers.remove(i).1);
+        json!({
+            "part": part,
+            "answer": answer,
+            "seconds": elapsed.as_secs_f64(),
+            "details": mem::take(&mut r.details),
+        })
+    });
+    println!("{}", line);
+}
+
+// Prints whatever was recorded outside `profile::part`, after anything the
+// solution printed along the way.
+pub fn finish() {
+    if !json_requested() {
+        return;
+    }
+
+    REPORT.with_borrow_mut(|r| {
+        for (part, answer) in mem::take(&mut r.answers) {
+            let line = json!({
+                "part": part,
+                "answer": answer,
+                "seconds": null,
+                "details": mem::take(&mut r.details),
+            });
+            println!("{}", line);
+        }
+        if !r.details.is_empty() {
+            println!("{}", json!({ "details": mem::take(&mut r.details) }));
+        }
+    });
+}
@@ -0,0 +1,37 @@
+use std::ops::Range;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+// Runs on rayon's work-stealing pool with the `parallel` feature and in a plain
+// loop otherwise. Results always come back in input order, so anything folded
+// from them afterwards matches a sequential run.
+#[cfg(feature = "parallel")]
+pub fn map<T: Sync, U: Send>(items: &[T], f: impl Fn(&T) -> U + Sync + Send) -> Vec<U> {
+    items.par_iter().map(f).collect()
+}
+
+#[cfg(feature = "parallel")]
+pub fn map_owned<T: Send, U: Send>(items: Vec<T>, f: impl Fn(T) -> U + Sync + Send) -> Vec<U> {
+    items.into_par_iter().map(f).collect()
+}
+
+#[cfg(feature = "parallel")]
+pub fn map_range<U: Send>(range: Range<usize>, f: impl Fn(usize) -> U + Sync + Send) -> Vec<U> {
+    range.into_par_iter().map(f).collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+pub fn map<T: Sync, U: Send>(items: &[T], f: impl Fn(&T) -> U + Sync + Send) -> Vec<U> {
+    items.iter().map(f).collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+pub fn map_owned<T: Send, U: Send>(items: Vec<T>, f: impl Fn(T) -> U + Sync + Send) -> Vec<U> {
+    items.into_iter().map(f).collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+pub fn map_range<U: Send>(range: Range<usize>, f: impl Fn(usize) -> U + Sync + Send) -> Vec<U> {
+    range.map(f).collect()
+}
@@ -0,0 +1,28 @@
+use std::env;
+use std::str::FromStr;
+
+// The runner passes each day's settings from `.aoc.toml`, and any `--param`
+// flags it was given, as `--param name=value`. Without one the solution's own
+// default is used, so running the binary directly behaves as before.
+pub fn get<T: FromStr>(name: &str, default: T) -> T {
+    find(name).unwrap_or(default)
+}
+
+// For settings that have no sensible default and are simply off when absent.
+pub fn find<T: FromStr>(name: &str) -> Option<T> {
+    let args: Vec<String> = env::args().collect();
+    // The last occurrence wins, as it would for any other repeated flag.
+    let value = args
+        .windows(2)
+        .rev()
+        .filter(|w| w[0] == "--param")
+        .filter_map(|w| w[1].split_once('='))
+        .find(|(n, _)| *n == name)
+        .map(|(_, v)| v)?;
+
+    Some(
+        value
+            .parse::<T>()
+            .unwrap_or_else(|_| panic!("Couldn't parse --param {}={}", name, value)),
+    )
+}
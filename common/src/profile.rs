@@ -0,0 +1,162 @@
+use crate::output;
+#[cfg(feature = "profile")]
+use std::cell::Cell;
+use std::fmt::Display;
+use std::time::Instant;
+
+#[cfg(feature = "profile")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    pub static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+    pub static BYTES: AtomicUsize = AtomicUsize::new(0);
+    pub static CURRENT: AtomicUsize = AtomicUsize::new(0);
+    pub static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+    pub struct CountingAllocator;
+
+    fn record(grown: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(grown, Ordering::Relaxed);
+        let current = CURRENT.fetch_add(grown, Ordering::Relaxed) + grown;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                record(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            if !new_ptr.is_null() {
+                CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+                record(new_size);
+            }
+            new_ptr
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAllocator = CountingAllocator;
+
+    pub fn reset_peak() -> usize {
+        let peak = PEAK.load(Ordering::Relaxed);
+        PEAK.store(CURRENT.load(Ordering::Relaxed), Ordering::Relaxed);
+        peak
+    }
+
+    pub fn totals() -> (usize, usize) {
+        (
+            ALLOCATIONS.load(Ordering::Relaxed),
+            BYTES.load(Ordering::Relaxed),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllocStats {
+    pub peak: usize,
+    pub allocations: usize,
+    pub bytes: usize,
+}
+
+struct Bytes(usize);
+
+impl Display for Bytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let units = ["B", "KiB", "MiB", "GiB"];
+        let mut value = self.0 as f64;
+        let mut unit = 0;
+        while value >= 1024.0 && unit < units.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+        write!(f, "{:.1} {}", value, units[unit])
+    }
+}
+
+impl Display for AllocStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "peak {}, {} allocations, {} allocated",
+            Bytes(self.peak),
+            self.allocations,
+            Bytes(self.bytes)
+        )
+    }
+}
+
+#[cfg(feature = "profile")]
+thread_local! {
+    static PARSE: Cell<AllocStats> = const { Cell::new(AllocStats { peak: 0, allocations: 0, bytes: 0 }) };
+    static SOLVE_PEAK: Cell<usize> = const { Cell::new(0) };
+}
+
+// Everything a part allocates outside of `parse` is attributed to solving.
+#[cfg(feature = "profile")]
+pub fn part<T, F: FnOnce() -> T>(part: u32, f: F) -> T {
+    PARSE.set(AllocStats::default());
+    counting::reset_peak();
+    SOLVE_PEAK.set(0);
+    let (allocations, bytes) = counting::totals();
+    let start = Instant::now();
+
+    let result = f();
+
+    output::timed(part, start.elapsed());
+    let (end_allocations, end_bytes) = counting::totals();
+    let parse = PARSE.get();
+    let solve = AllocStats {
+        peak: SOLVE_PEAK.get().max(counting::reset_peak()),
+        allocations: end_allocations - allocations - parse.allocations,
+        bytes: end_bytes - bytes - parse.bytes,
+    };
+
+    eprintln!("Profile Part {} parse: {}", part, parse);
+    eprintln!("Profile Part {} solve: {}", part, solve);
+
+    result
+}
+
+#[cfg(not(feature = "profile"))]
+pub fn part<T, F: FnOnce() -> T>(part: u32, f: F) -> T {
+    let start = Instant::now();
+    let result = f();
+    output::timed(part, start.elapsed());
+    result
+}
+
+#[cfg(feature = "profile")]
+pub fn parse<T, F: FnOnce() -> T>(f: F) -> T {
+    SOLVE_PEAK.set(SOLVE_PEAK.get().max(counting::reset_peak()));
+    let (allocations, bytes) = counting::totals();
+
+    let result = f();
+
+    let (end_allocations, end_bytes) = counting::totals();
+    let previous = PARSE.get();
+    PARSE.set(AllocStats {
+        peak: previous.peak.max(counting::reset_peak()),
+        allocations: previous.allocations + end_allocations - allocations,
+        bytes: previous.bytes + end_bytes - bytes,
+    });
+
+    result
+}
+
+#[cfg(not(feature = "profile"))]
+pub fn parse<T, F: FnOnce() -> T>(f: F) -> T {
+    f()
+}
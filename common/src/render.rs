@@ -0,0 +1,148 @@
+use std::env;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ansi,
+    Html,
+}
+
+pub fn requested() -> Option<Format> {
+    env::args().find_map(|a| match a.as_str() {
+        "--ansi" => Some(Format::Ansi),
+        "--html" => Some(Format::Html),
+        _ => None,
+    })
+}
+
+// How one kind of cell is drawn: an SGR code for the terminal and a CSS colour
+// for HTML. `name` is the legend entry and the HTML class.
+#[derive(Debug, Clone, Copy)]
+pub struct Style {
+    pub name: &'static str,
+    pub ansi: &'static str,
+    pub css: &'static str,
+}
+
+// A text grid with a style per cell and notes trailing each line. Unstyled
+// cells are drawn as they are.
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    cells: Vec<Vec<(char, Option<Style>)>>,
+    notes: Vec<Vec<String>>,
+}
+
+impl Canvas {
+    pub fn new(text: &str) -> Canvas {
+        let cells: Vec<Vec<(char, Option<Style>)>> = text
+            .lines()
+            .map(|l| l.chars().map(|c| (c, None)).collect())
+            .collect();
+        let notes = vec![vec![]; cells.len()];
+        Canvas { cells, notes }
+    }
+
+    // Cells outside the text are ignored.
+    pub fn paint(&mut self, row: usize, column: usize, style: Style) {
+        if let Some(cell) = self.cells.get_mut(row).and_then(|r| r.get_mut(column)) {
+            cell.1 = Some(style);
+        }
+    }
+
+    pub fn note(&mut self, row: usize, note: String) {
+        if let Some(notes) = self.notes.get_mut(row) {
+            notes.push(note);
+        }
+    }
+
+    // Neighbouring cells in the same style share one escape or span.
+    fn runs(row: &[(char, Option<Style>)]) -> Vec<(String, Option<Style>)> {
+        let mut runs: Vec<(String, Option<Style>)> = vec![];
+        for (c, style) in row {
+            match runs.last_mut() {
+                Some((text, last)) if last.map(|s| s.name) == style.map(|s| s.name) => {
+                    text.push(*c)
+                }
+                _ => runs.push((c.to_string(), *style)),
+            }
+        }
+        runs
+    }
+
+    fn ansi(&self, legend: &[Style]) -> String {
+        let mut lines: Vec<String> = vec![legend
+            .iter()
+            .map(|s| format!("\x1b[{}m{}\x1b[0m", s.ansi, s.name))
+            .collect::<Vec<String>>()
+            .join("  ")];
+        for (row, notes) in self.cells.iter().zip(&self.notes) {
+            let mut line: String = Canvas::runs(row)
+                .iter()
+                .map(|(text, style)| match style {
+                    Some(s) => format!("\x1b[{}m{}\x1b[0m", s.ansi, text),
+                    None => text.clone(),
+                })
+                .collect();
+            if !notes.is_empty() {
+                line.push_str(&format!("  {}", notes.join(", ")));
+            }
+            lines.push(line);
+        }
+        lines.join("\n") + "\n"
+    }
+
+    fn html(&self, legend: &[Style]) -> String {
+        let mut lines: Vec<String> = vec![
+            "<!DOCTYPE html>".to_string(),
+            "<html>".to_string(),
+            "<head>".to_string(),
+            "<meta charset=\"utf-8\">".to_string(),
+            "<style>".to_string(),
+            "pre { font-family: monospace; background: #0f0f23; color: #cccccc; padding: 1em; }"
+                .to_string(),
+            ".note { color: #999999; }".to_string(),
+        ];
+        lines.extend(
+            legend
+                .iter()
+                .map(|s| format!(".{} {{ color: {}; font-weight: bold; }}", s.name, s.css)),
+        );
+        lines.extend(["</style>", "</head>", "<body>", "<p>"].map(String::from));
+        lines.extend(
+            legend
+                .iter()
+                .map(|s| format!("<span class=\"{0}\">{0}</span>", s.name)),
+        );
+        lines.extend(["</p>".to_string(), "<pre>".to_string()]);
+        for (row, notes) in self.cells.iter().zip(&self.notes) {
+            let mut line: String = Canvas::runs(row)
+                .iter()
+                .map(|(text, style)| match style {
+                    Some(s) => format!("<span class=\"{}\">{}</span>", s.name, escape(text)),
+                    None => escape(text),
+                })
+                .collect();
+            if !notes.is_empty() {
+                line.push_str(&format!(
+                    "  <span class=\"note\">{}</span>",
+                    escape(&notes.join(", "))
+                ));
+            }
+            lines.push(line);
+        }
+        lines.extend(["</pre>", "</body>", "</html>"].map(String::from));
+        lines.join("\n") + "\n"
+    }
+
+    pub fn render(&self, format: Format, legend: &[Style]) -> String {
+        match format {
+            Format::Ansi => self.ansi(legend),
+            Format::Html => self.html(legend),
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}